@@ -0,0 +1,57 @@
+﻿using System.Collections.Concurrent;
+using System.Security.Cryptography;
+
+namespace TodoAPI_MVC.Authentication
+{
+    public interface IStreamTickets
+    {
+        string Issue(int userId);
+        bool TryRedeem(string? ticket, out int userId);
+    }
+
+    /// <summary>
+    /// Short lived, single use tickets that open an event stream in place of the JWT.
+    /// EventSource can't send headers, and a JWT in the stream's URL would end up in logs and browser history.
+    /// </summary>
+    public class StreamTickets : IStreamTickets
+    {
+        private const int TicketBytes = 32;
+        private static readonly TimeSpan Lifetime = TimeSpan.FromSeconds(30);
+
+        private readonly ConcurrentDictionary<string, (int UserId, DateTime ExpiresAt)> _tickets = new();
+
+        public string Issue(int userId)
+        {
+            RemoveExpired();
+
+            var ticket = Convert.ToHexString(RandomNumberGenerator.GetBytes(TicketBytes));
+            _tickets[ticket] = (userId, DateTime.UtcNow + Lifetime);
+
+            return ticket;
+        }
+
+        public bool TryRedeem(string? ticket, out int userId)
+        {
+            userId = default;
+
+            if (ticket is null || !_tickets.TryRemove(ticket, out var issued))
+                return false;
+
+            if (issued.ExpiresAt < DateTime.UtcNow)
+                return false;
+
+            userId = issued.UserId;
+            return true;
+        }
+
+        private void RemoveExpired()
+        {
+            var now = DateTime.UtcNow;
+            foreach (var (ticket, issued) in _tickets)
+            {
+                if (issued.ExpiresAt < now)
+                    _tickets.TryRemove(ticket, out _);
+            }
+        }
+    }
+}
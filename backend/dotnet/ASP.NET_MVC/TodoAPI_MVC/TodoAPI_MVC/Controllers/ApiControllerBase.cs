@@ -8,6 +8,13 @@ namespace TodoAPI_MVC.Controllers
 {
     public abstract class ApiControllerBase : ControllerBase
     {
+        /// <summary>
+        /// Tells apart errors that share a status code, e.g. a task of another user
+        /// from a token that is no longer valid, both answered with 403.
+        /// </summary>
+        public const string ErrorCodeHeader = "X-Error-Code";
+        public const string NotOwnedErrorCode = "not-owned";
+
         protected readonly UserManager<User> _userManager;
         protected readonly SignInManager<User> _signInManager;
 
@@ -40,6 +47,8 @@ namespace TodoAPI_MVC.Controllers
             {
                 Models.StatusCode.Ok => base.Ok(dbResult.Data),
                 Models.StatusCode.Error => base.BadRequest(GetErrorString(dbResult.ErrorData)),
+                Models.StatusCode.NotFound => base.NotFound(GetErrorString(dbResult.ErrorData)),
+                Models.StatusCode.Forbidden => NotOwned(GetErrorString(dbResult.ErrorData)),
                 _ => base.StatusCode(500, GetErrorString(dbResult.ErrorData))
             };
         }
@@ -50,10 +59,18 @@ namespace TodoAPI_MVC.Controllers
             {
                 Models.StatusCode.Ok => Ok(),
                 Models.StatusCode.Error => BadRequest(GetErrorString(dbResult.ErrorData)),
+                Models.StatusCode.NotFound => NotFound(GetErrorString(dbResult.ErrorData)),
+                Models.StatusCode.Forbidden => NotOwned(GetErrorString(dbResult.ErrorData)),
                 _ => StatusCode(500, GetErrorString(dbResult.ErrorData))
             };
         }
 
+        private IActionResult NotOwned(string error)
+        {
+            Response.Headers[ErrorCodeHeader] = NotOwnedErrorCode;
+            return base.StatusCode(403, error);
+        }
+
         private static string GetErrorString(string[]? errorData)
         {
             return string.Join(Environment.NewLine, errorData ?? Array.Empty<string>());
@@ -1,9 +1,16 @@
-﻿using Microsoft.AspNetCore.Identity;
+﻿using Microsoft.AspNetCore.Authorization;
+using Microsoft.AspNetCore.Identity;
 using Microsoft.AspNetCore.Mvc;
+using Microsoft.Extensions.Options;
+using Microsoft.Net.Http.Headers;
+using System.Security.Cryptography;
+using System.Text.Json;
 using TodoAPI_MVC.Atributtes;
 using TodoAPI_MVC.Authentication;
+using TodoAPI_MVC.Database;
 using TodoAPI_MVC.Database.Interfaces;
 using TodoAPI_MVC.Models;
+using TodoAPI_MVC.Services;
 
 namespace TodoAPI_MVC.Controllers
 {
@@ -13,22 +20,31 @@ namespace TodoAPI_MVC.Controllers
     public class TasksController : ApiControllerBase
     {
         private readonly ITaskData _taskData;
+        private readonly ITaskEvents _taskEvents;
+        private readonly IStreamTickets _streamTickets;
 
         public TasksController(
             ITaskData taskData,
+            ITaskEvents taskEvents,
+            IStreamTickets streamTickets,
             UserManager<User> userManager,
             SignInManager<User> signInManager)
             : base(userManager, signInManager)
         {
             _taskData = taskData;
+            _taskEvents = taskEvents;
+            _streamTickets = streamTickets;
         }
 
         [HttpPost]
         public async Task<IActionResult> Create(
             TodoTask newTask, [FromQuery]bool asCompleted, CancellationToken cancellationToken)
         {
-            return ActionResult(
-                await _taskData.CreateAsync(newTask, await GetCurrentUserId(), asCompleted, cancellationToken));
+            var userId = await GetCurrentUserId();
+            var result = await _taskData.CreateAsync(newTask, userId, asCompleted, cancellationToken);
+            Publish(userId, TaskEvent.Created, result);
+
+            return ActionResult(result);
         }
 
         [HttpGet("{id:int}")]
@@ -38,33 +54,60 @@ namespace TodoAPI_MVC.Controllers
                 await _taskData.GetAsync(id, await GetCurrentUserId(), cancellationToken));
         }
 
+        /// <summary>
+        /// Sends the list along with its ETag, a list unchanged since the ETag in <c>If-None-Match</c>
+        /// is answered with an empty 304.
+        /// </summary>
         [HttpGet]
         public async Task<IActionResult> GetAllOwned(CancellationToken cancellationToken)
         {
-            return ActionResult(
-                await _taskData.GetAllOwnedAsync(await GetCurrentUserId(), cancellationToken));
+            var result = await _taskData.GetAllOwnedAsync(await GetCurrentUserId(), cancellationToken);
+            if (result.Code != Models.StatusCode.Ok || result.Data is null)
+                return ActionResult(result);
+
+            var etag = GetETag(result.Data);
+            Response.Headers.ETag = etag;
+            if (Request.GetTypedHeaders().IfNoneMatch.Any(t => t.Equals(EntityTagHeaderValue.Any) || t.Tag == etag))
+                return StatusCode(StatusCodes.Status304NotModified);
+
+            return ActionResult(result);
         }
 
         [HttpPatch("{id:int}")]
         public async Task<IActionResult> Update(
             int id, TodoTask updatedTask, CancellationToken cancellationToken)
         {
-            return ActionResult(
-                await _taskData.UpdateAsync(id, updatedTask, await GetCurrentUserId(), cancellationToken));
+            var userId = await GetCurrentUserId();
+            var result = await _taskData.UpdateAsync(id, updatedTask, userId, cancellationToken);
+            Publish(userId, TaskEvent.Updated, result);
+
+            return ActionResult(result);
         }
 
         [HttpPatch("{id:int}/toggle-completed")]
-        public async Task<IActionResult> ToggleCompleted(int id, CancellationToken cancellationToken)
+        public async Task<IActionResult> ToggleCompleted(
+            int id, [FromQuery]int utcOffsetMinutes, CancellationToken cancellationToken)
         {
-            return ActionResult(
-                await _taskData.ToggleCompletedAsync(id, await GetCurrentUserId(), cancellationToken));
+            var userId = await GetCurrentUserId();
+            var result = await _taskData.ToggleCompletedAsync(
+                id, userId, TimeSpan.FromMinutes(utcOffsetMinutes), cancellationToken);
+            var toggled = new DatabaseResult<TodoTask>(result.Code, result.Data.Task, result.ErrorData);
+            Publish(userId, TaskEvent.Updated, toggled);
+            if (result.Data.Next is TodoTask next)
+                Publish(userId, TaskEvent.Created, DatabaseResults.Ok(next));
+
+            return ActionResult(toggled);
         }
 
         [HttpDelete("{id:int}")]
         public async Task<IActionResult> Delete(int id, CancellationToken cancellationToken)
         {
-            return ActionResult(
-                await _taskData.DeleteAsync(id, await GetCurrentUserId(), cancellationToken));
+            var userId = await GetCurrentUserId();
+            var result = await _taskData.DeleteAsync(id, userId, cancellationToken);
+            if (userId is int validUserId && result.Code == Models.StatusCode.Ok)
+                _taskEvents.Publish(validUserId, new(TaskEvent.Deleted, new { Id = id }));
+
+            return ActionResult(result);
         }
 
         [HttpGet("all")]
@@ -73,5 +116,66 @@ namespace TodoAPI_MVC.Controllers
         {
             return ActionResult(await _taskData.GetAllAsync(cancellationToken));
         }
+
+        /// <summary>
+        /// Issues the ticket that opens the event stream, see <see cref="Events"/>.
+        /// </summary>
+        [HttpPost("events/ticket")]
+        public async Task<IActionResult> EventsTicket()
+        {
+            if (await GetCurrentUserId() is not int userId)
+                return Unauthorized();
+
+            return Ok(new { Ticket = _streamTickets.Issue(userId) });
+        }
+
+        /// <summary>
+        /// Streams the changes made to the user's tasks as server-sent events,
+        /// <c>created</c> and <c>updated</c> with the task and <c>deleted</c> with its id.
+        /// EventSource can't send the JWT, so the stream is opened with a ticket from <see cref="EventsTicket"/>.
+        /// </summary>
+        [AllowAnonymous]
+        [HttpGet("events")]
+        public async Task<IActionResult> Events([FromQuery]string? ticket, CancellationToken cancellationToken)
+        {
+            if (!_streamTickets.TryRedeem(ticket, out var userId))
+                return Unauthorized();
+
+            var jsonSerializerOptions = HttpContext.RequestServices
+                .GetRequiredService<IOptions<JsonOptions>>().Value.JsonSerializerOptions;
+            var events = _taskEvents.Subscribe(userId, cancellationToken);
+
+            Response.ContentType = "text/event-stream";
+            Response.Headers.CacheControl = "no-cache";
+            await Response.Body.FlushAsync(cancellationToken);
+
+            try
+            {
+                await foreach (var taskEvent in events.ReadAllAsync(cancellationToken))
+                {
+                    var data = JsonSerializer.Serialize(taskEvent.Data, jsonSerializerOptions);
+                    await Response.WriteAsync($"event: {taskEvent.Name}\ndata: {data}\n\n", cancellationToken);
+                    await Response.Body.FlushAsync(cancellationToken);
+                }
+            }
+            catch (OperationCanceledException)
+            {
+                // the client went away
+            }
+
+            return new EmptyResult();
+        }
+
+        private static string GetETag(TodoTask[] tasks)
+        {
+            var hash = SHA256.HashData(JsonSerializer.SerializeToUtf8Bytes(tasks));
+            return $"\"{Convert.ToHexString(hash)}\"";
+        }
+
+        private void Publish(int? userId, string name, IDatabaseResult<TodoTask> result)
+        {
+            if (userId is int validUserId && result.Code == Models.StatusCode.Ok)
+                _taskEvents.Publish(validUserId, new(name, result.Data));
+        }
     }
 }
@@ -22,6 +22,24 @@ namespace TodoAPI_MVC.Database
             };
         }
 
+        public static IDatabaseResult<T> NotFound<T>(params string[]? error)
+        {
+            return new DatabaseResult<T>
+            {
+                Code = StatusCode.NotFound,
+                ErrorData = error
+            };
+        }
+
+        public static IDatabaseResult<T> Forbidden<T>(params string[]? error)
+        {
+            return new DatabaseResult<T>
+            {
+                Code = StatusCode.Forbidden,
+                ErrorData = error
+            };
+        }
+
         public static IDatabaseResult Ok()
         {
             return new DatabaseResult
@@ -38,5 +56,23 @@ namespace TodoAPI_MVC.Database
                 ErrorData = error
             };
         }
+
+        public static IDatabaseResult NotFound(params string[]? error)
+        {
+            return new DatabaseResult
+            {
+                Code = StatusCode.NotFound,
+                ErrorData = error
+            };
+        }
+
+        public static IDatabaseResult Forbidden(params string[]? error)
+        {
+            return new DatabaseResult
+            {
+                Code = StatusCode.Forbidden,
+                ErrorData = error
+            };
+        }
     }
 }
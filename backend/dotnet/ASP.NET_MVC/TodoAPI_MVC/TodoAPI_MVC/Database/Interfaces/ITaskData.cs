@@ -9,7 +9,7 @@ namespace TodoAPI_MVC.Database.Interfaces
         Task<IDatabaseResult<TodoTask>> GetAsync(int id, int? userId, CancellationToken cancellationToken = default);
         Task<IDatabaseResult<TodoTask[]>> GetAllOwnedAsync(int? userId, CancellationToken cancellationToken = default);
         Task<IDatabaseResult<TodoTask[]>> GetAllAsync(CancellationToken cancellationToken = default);
-        Task<IDatabaseResult<TodoTask>> ToggleCompletedAsync(int id, int? userId, CancellationToken cancellationToken = default);
+        Task<IDatabaseResult<ToggledTask>> ToggleCompletedAsync(int id, int? userId, TimeSpan utcOffset, CancellationToken cancellationToken = default);
         Task<IDatabaseResult<TodoTask>> UpdateAsync(int id, TodoTask task, int? userId, CancellationToken cancellationToken = default);
         Task<IDatabaseResult<TodoTask[]>> CreateDefaultsAsync(int? userId, CancellationToken cancellationToken = default);
     }
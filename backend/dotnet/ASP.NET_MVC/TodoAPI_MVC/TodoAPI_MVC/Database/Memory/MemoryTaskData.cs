@@ -36,13 +36,11 @@ namespace TodoAPI_MVC.Database.Memory
             var taskIndex = _tasks.FindIndex(t => t.Id == id);
 
             if (taskIndex < 0)
-                return Task.FromResult(DatabaseResults.Error<TodoTask>("Task not found!"));
+                return Task.FromResult(DatabaseResults.NotFound<TodoTask>("Task not found!"));
 
             var taskToUpdate = _tasks[taskIndex];
-            var ownershipError = EnsureOwnership(taskToUpdate, userId);
-
-            if (ownershipError is string oError)
-                return Task.FromResult(DatabaseResults.Error<TodoTask>(oError));
+            if (EnsureOwnership<TodoTask>(taskToUpdate, userId) is { } ownershipError)
+                return Task.FromResult(ownershipError);
 
             _tasks[taskIndex] = task;
 
@@ -53,13 +51,12 @@ namespace TodoAPI_MVC.Database.Memory
             int id, int? userId, CancellationToken _ = default)
         {
             if (!_tasks.Any(t => t.Id == id))
-                return Task.FromResult(DatabaseResults.Error("Task not found!"));
+                return Task.FromResult(DatabaseResults.NotFound("Task not found!"));
 
             var task = _tasks.First(t => t.Id == id);
-            var ownershipError = EnsureOwnership(task, userId);
-
-            if (ownershipError is string oError)
-                return Task.FromResult(DatabaseResults.Error(oError));
+            if (EnsureOwnership<TodoTask>(task, userId) is { } ownershipError)
+                return Task.FromResult<IDatabaseResult>(
+                    new DatabaseResult(ownershipError.Code, ownershipError.ErrorData));
 
             _tasks.Remove(task);
             return Task.FromResult(DatabaseResults.Ok());
@@ -76,26 +73,36 @@ namespace TodoAPI_MVC.Database.Memory
                 _tasks.Where(t => ownedTasksIds.Any(i => i == t.Id)).ToArray()));
         }
 
-        public Task<IDatabaseResult<TodoTask>> ToggleCompletedAsync(
-            int id, int? userId, CancellationToken _ = default)
+        public Task<IDatabaseResult<ToggledTask>> ToggleCompletedAsync(
+            int id, int? userId, TimeSpan utcOffset, CancellationToken _ = default)
         {
             var taskIndex = _tasks.FindIndex(0, t => t.Id == id);
             if (taskIndex < 0)
-                return Task.FromResult(DatabaseResults.Error<TodoTask>("Task not found!"));
+                return Task.FromResult(DatabaseResults.NotFound<ToggledTask>("Task not found!"));
 
             var task = _tasks[taskIndex];
-            var ownershipError = EnsureOwnership(task, userId);
-
-            if (ownershipError is string oError)
-                return Task.FromResult(DatabaseResults.Error<TodoTask>(oError));
+            if (EnsureOwnership<ToggledTask>(task, userId) is { } ownershipError)
+                return Task.FromResult(ownershipError);
 
             task.CompletedAt = task.CompletedAt is null
                 ? DateTime.Now
                 : null;
 
+            // the next instance takes over the rule, so completing this one again doesn't repeat it twice
+            TodoTask? created = null;
+            if (task.CompletedAt is DateTime completedAt &&
+                task.NextOccurrence(completedAt, utcOffset) is TodoTask next)
+            {
+                task.Recurrence = null;
+                next.Id = _tasks.GetNextValue(t => t.Id);
+                _tasks.Add(next);
+                _taskOwners[next.Id] = _taskOwners[task.Id];
+                created = next;
+            }
+
             _tasks[taskIndex] = task;
 
-            return Task.FromResult(DatabaseResults.Ok(task));
+            return Task.FromResult(DatabaseResults.Ok(new ToggledTask(task, created)));
         }
 
         public Task<IDatabaseResult<TodoTask>> GetAsync(
@@ -103,27 +110,25 @@ namespace TodoAPI_MVC.Database.Memory
         {
             var taskIndex = _tasks.FindIndex(t => t.Id == id);
             if (taskIndex < 0)
-                return Task.FromResult(DatabaseResults.Error<TodoTask>("Task not found!"));
+                return Task.FromResult(DatabaseResults.NotFound<TodoTask>("Task not found!"));
 
             var task = _tasks[taskIndex];
-            var ownershipError = EnsureOwnership(task, userId);
-
-            if (ownershipError is string oError)
-                return Task.FromResult(DatabaseResults.Error<TodoTask>(oError));
+            if (EnsureOwnership<TodoTask>(task, userId) is { } ownershipError)
+                return Task.FromResult(ownershipError);
 
             return Task.FromResult(DatabaseResults.Ok(_tasks[taskIndex]));
         }
 
-        private string? EnsureOwnership(TodoTask taskToUpdate, int? userId)
+        private IDatabaseResult<T>? EnsureOwnership<T>(TodoTask taskToUpdate, int? userId)
         {
             if (userId is not int id)
-                return "Invalid user!";
+                return DatabaseResults.Error<T>("Invalid user!");
 
             if (!_taskOwners.TryGetValue(taskToUpdate.Id, out var taskOwnerId))
-                return "Task not found!";
+                return DatabaseResults.NotFound<T>("Task not found!");
 
             if (taskOwnerId != id)
-                return "Task is not owned by the user!";
+                return DatabaseResults.Forbidden<T>("Task is not owned by the user!");
 
             return null;
         }
@@ -3,6 +3,7 @@ using Npgsql.Schema;
 using System.Collections.ObjectModel;
 using System.Reflection;
 using System.Text;
+using System.Text.Json;
 using TodoAPI_MVC.Database.Interfaces;
 
 namespace TodoAPI_MVC.Database.Postgres
@@ -140,6 +141,17 @@ namespace TodoAPI_MVC.Database.Postgres
                         if (value.GetType().IsAssignableTo(propertyData.PropertyInfo.PropertyType))
                             propertyData.PropertyInfo.SetValue(item, value);
 
+                        // arrays of anything but strings are stored as JSONB
+                        if (propertyType.IsArray && value is string json)
+                            propertyData.PropertyInfo.SetValue(item, JsonSerializer.Deserialize(json, propertyType));
+
+                        // JSON the API only passes through is kept as it is
+                        if (propertyType == typeof(JsonElement) && value is string rawJson)
+                        {
+                            using var document = JsonDocument.Parse(rawJson);
+                            propertyData.PropertyInfo.SetValue(item, document.RootElement.Clone());
+                        }
+
                         if (propertyType.IsEnum && value is string stringValue &&
                             Enum.TryParse(propertyType, stringValue, out var enumValue))
                         {
@@ -68,7 +68,7 @@ namespace TodoAPI_MVC.Database.Postgres
             {
                 var constraint = _const((TodoTask t) => t.Id == id && t.UserId == userId);
                 if (await _dataSource.DeleteRows(TableName, constraint, cancellationToken) == 0)
-                    return DatabaseResults.Error("Task not found!");
+                    return DatabaseResults.NotFound("Task not found!");
 
                 return DatabaseResults.Ok();
             }
@@ -116,12 +116,16 @@ namespace TodoAPI_MVC.Database.Postgres
         {
             try
             {
-                var constraint = _const((TodoTask t) => t.Id == id && t.UserId == userId);
+                // read by id alone, so a task of another user can be told apart from a missing one
+                var constraint = _const((TodoTask t) => t.Id == id);
                 var tasks = await _dataSource.ReadRows<TodoTask>(
                     TableName, constraint, cancellationToken);
 
                 if (!tasks.Any())
-                    return DatabaseResults.Error<TodoTask>("Task not found!");
+                    return DatabaseResults.NotFound<TodoTask>("Task not found!");
+
+                if (tasks[0].UserId != userId)
+                    return DatabaseResults.Forbidden<TodoTask>("Task is not owned by the user!");
 
                 return DatabaseResults.Ok(tasks[0]);
             }
@@ -131,11 +135,26 @@ namespace TodoAPI_MVC.Database.Postgres
             }
         }
 
-        public async Task<IDatabaseResult<TodoTask>> ToggleCompletedAsync(
-            int id, int? userId, CancellationToken cancellationToken = default)
+        public async Task<IDatabaseResult<ToggledTask>> ToggleCompletedAsync(
+            int id, int? userId, TimeSpan utcOffset, CancellationToken cancellationToken = default)
         {
             try
             {
+                var current = await GetAsync(id, userId, cancellationToken);
+                if (current.Code != StatusCode.Ok)
+                    return new DatabaseResult<ToggledTask>(current.Code, default, current.ErrorData);
+
+                var completedAt = DateTime.Now;
+                if (current.Data.CompletedAt is null &&
+                    current.Data.NextOccurrence(completedAt, utcOffset) is TodoTask next)
+                {
+                    var recurred = await CompleteRecurringAsync(id, userId, completedAt, next, cancellationToken);
+                    if (recurred.Any(t => t.Id == id))
+                        return DatabaseResults.Ok(new ToggledTask(
+                            recurred.Single(t => t.Id == id),
+                            recurred.Where(t => t.Id != id).Cast<TodoTask?>().SingleOrDefault()));
+                }
+
                 var commandString = $"""
                     UPDATE tasks 
                         SET completed_at = 
@@ -151,16 +170,48 @@ namespace TodoAPI_MVC.Database.Postgres
                     commandString, cancellationToken);
 
                 if (!tasks.Any())
-                    return DatabaseResults.Error<TodoTask>("Task not found!");
+                    return DatabaseResults.NotFound<ToggledTask>("Task not found!");
 
-                return DatabaseResults.Ok(tasks[0]);
+                return DatabaseResults.Ok(new ToggledTask(tasks[0], null));
             }
             catch (Exception error)
             {
-                return DatabaseResults.Error<TodoTask>(error.Message);
+                return DatabaseResults.Error<ToggledTask>(error.Message);
             }
         }
 
+        /// <summary>
+        /// Completes the task, takes the rule off it and creates its next instance in a single statement,
+        /// so a repeated request can't create the next instance twice.
+        /// Returns both tasks, nothing if the task was completed or lost its rule in the meantime.
+        /// </summary>
+        private async Task<IList<TodoTask>> CompleteRecurringAsync(
+            int id, int? userId, DateTime completedAt, TodoTask next, CancellationToken cancellationToken)
+        {
+            var commandString = $"""
+                WITH completed AS (
+                    UPDATE tasks
+                        SET completed_at = {_dbService.GetSqlValue(completedAt)}, recurrence = NULL
+                    WHERE {_const((TodoTask t) => t.Id == id && t.UserId == userId).ToSqlString()}
+                        AND completed_at IS NULL AND recurrence IS NOT NULL
+                    RETURNING *
+                ), next AS (
+                    INSERT INTO tasks (title, priority, description, tags, user_id, due_at, subtasks, recurrence)
+                    SELECT title, priority, description, tags, user_id,
+                        {_dbService.GetSqlValue(next.DueAt)}::TIMESTAMP,
+                        {_dbService.GetSqlValue(next.Subtasks)}::JSONB,
+                        {_dbService.GetSqlValue(next.Recurrence)}
+                    FROM completed
+                    RETURNING *
+                )
+                SELECT * FROM completed
+                UNION ALL
+                SELECT * FROM next
+                """;
+
+            return await _dataSource.ExecuteQuery<TodoTask>(commandString, cancellationToken);
+        }
+
         public async Task<IDatabaseResult<TodoTask>> UpdateAsync(
             int id, TodoTask task, int? userId, CancellationToken cancellationToken = default)
         {
@@ -184,7 +235,7 @@ namespace TodoAPI_MVC.Database.Postgres
                     TableName, task, constraint, cancellationToken);
 
                 if (!tasks.Any())
-                    return DatabaseResults.Error<TodoTask>("Task not found!");
+                    return DatabaseResults.NotFound<TodoTask>("Task not found!");
 
                 return DatabaseResults.Ok(tasks[0]);
             }
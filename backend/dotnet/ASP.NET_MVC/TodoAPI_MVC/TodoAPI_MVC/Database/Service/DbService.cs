@@ -1,5 +1,6 @@
 ﻿using System.Diagnostics.CodeAnalysis;
 using System.Reflection;
+using System.Text.Json;
 using TodoAPI_MVC.Atributtes;
 using TodoAPI_MVC.Database.Interfaces;
 using TodoAPI_MVC.Services;
@@ -29,12 +30,18 @@ namespace TodoAPI_MVC.Database.Service
                 return converter.Convert(value);
             }
 
-            if (value is null)
+            if (value is null or JsonElement { ValueKind: JsonValueKind.Null or JsonValueKind.Undefined })
                 return "null";
             if (value is string or char)
-                return $"'{value}'";
+                return Quote($"{value}");
             if (value is DateTime dateTime)
                 return $"'{dateTime.ToString(Formats.Date)}'";
+            if (value is string[] strings)
+                return $"ARRAY[{string.Join(", ", strings.Select(GetSqlValue))}]::TEXT[]";
+            if (value is JsonElement json)
+                return $"{Quote(json.GetRawText())}::JSONB";
+            if (valueType?.IsArray == true)
+                return $"{Quote(JsonSerializer.Serialize(value))}::JSONB";
             if (valueType?.IsEnum == true)
                 return $"{(int)value}";
 
@@ -70,6 +77,11 @@ namespace TodoAPI_MVC.Database.Service
         }
 
         private string ApplyNamingPolicy(string name) => Options.NamingPolicy.ConvertName(name);
+
+        /// <summary>
+        /// A string literal of the text, with its quotes doubled so it can't end the literal early.
+        /// </summary>
+        private static string Quote(string text) => $"'{text.Replace("'", "''")}'";
     }
 
     public class DbServiceOptions
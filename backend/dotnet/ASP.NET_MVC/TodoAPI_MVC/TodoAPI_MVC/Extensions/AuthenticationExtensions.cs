@@ -41,6 +41,7 @@ namespace TodoAPI_MVC.Extensions
                 });
 
             builder.Services.AddSingleton<IRevokedTokens, RevokedTokens>();
+            builder.Services.AddSingleton<IStreamTickets, StreamTickets>();
             builder.Services.AddSingleton<IAuthenticationService, AuthenticationService>();
             builder.Services.AddClaims();
         }
@@ -5,6 +5,7 @@ namespace TodoAPI_MVC.Middleware
 {
     public class WrapResponsesMiddleware
     {
+        private const string EventStreamContentType = "text/event-stream";
         private readonly RequestDelegate _next;
 
         public WrapResponsesMiddleware(RequestDelegate next)
@@ -14,6 +15,13 @@ namespace TodoAPI_MVC.Middleware
 
         public async Task Invoke(HttpContext httpContext)
         {
+            // event streams never end, so they go out unwrapped as they are written
+            if (httpContext.Request.Headers.Accept.Any(a => a?.Contains(EventStreamContentType) == true))
+            {
+                await _next(httpContext).ConfigureAwait(false);
+                return;
+            }
+
             var jsonSerializerOptions = httpContext.RequestServices.GetService<JsonSerializerOptions>();
             var originBody = httpContext.Response.Body;
             httpContext.Request.EnableBuffering();
@@ -0,0 +1,113 @@
+﻿using System.Globalization;
+using System.Text.Json;
+
+namespace TodoAPI_MVC.Models
+{
+    /// <summary>
+    /// How a task repeats, as the frontend stores it in <see cref="TodoTask.Recurrence"/>:
+    /// <c>{"frequency": {"kind": "weekly", "weekdays": [0, 4]}, "until": "2023-12-31"}</c>.
+    /// </summary>
+    public record struct RecurrenceRule(
+        string Kind,
+        int[] Weekdays,
+        int Day,
+        int Days,
+        DateTime? Until)
+    {
+        public const string Daily = "daily";
+        public const string Weekly = "weekly";
+        public const string Monthly = "monthly";
+        public const string EveryNDays = "every_n_days";
+        private const string UntilFormat = "yyyy-MM-dd";
+
+        public static RecurrenceRule? Parse(JsonElement? json)
+        {
+            if (json is not JsonElement { ValueKind: JsonValueKind.Object } rule ||
+                !rule.TryGetProperty("frequency", out var frequency) ||
+                frequency.ValueKind != JsonValueKind.Object ||
+                !frequency.TryGetProperty("kind", out var kind) ||
+                kind.ValueKind != JsonValueKind.String)
+                return null;
+
+            int GetNumber(JsonElement element) =>
+                element.ValueKind == JsonValueKind.Number && element.TryGetInt32(out var number) ? number : -1;
+
+            var weekdays = frequency.TryGetProperty("weekdays", out var days) && days.ValueKind == JsonValueKind.Array
+                ? days.EnumerateArray().Select(GetNumber).ToArray()
+                : Array.Empty<int>();
+
+            // a malformed end is treated as no end
+            DateTime? until = rule.TryGetProperty("until", out var untilJson) &&
+                untilJson.ValueKind == JsonValueKind.String &&
+                DateTime.TryParseExact(
+                    untilJson.GetString(), UntilFormat, CultureInfo.InvariantCulture, DateTimeStyles.None, out var date)
+                ? date
+                : null;
+
+            return new RecurrenceRule(
+                kind.GetString()!,
+                weekdays,
+                frequency.TryGetProperty("day", out var day) ? GetNumber(day) : -1,
+                frequency.TryGetProperty("days", out var n) ? GetNumber(n) : -1,
+                until);
+        }
+
+        /// <summary>
+        /// The first occurrence on a day after <paramref name="completedAt"/>, at <paramref name="time"/>.
+        /// Null once the rule has ended or can't produce another date.
+        /// </summary>
+        public DateTime? NextOccurrence(DateTime completedAt, TimeSpan time)
+        {
+            if (NextDate(completedAt.Date) is not DateTime date)
+                return null;
+
+            if (Until is DateTime until && date > until)
+                return null;
+
+            return date + time;
+        }
+
+        private DateTime? NextDate(DateTime after)
+        {
+            switch (Kind)
+            {
+                case Daily:
+                    return after.AddDays(1);
+
+                case EveryNDays:
+                    return Days > 0 ? after.AddDays(Days) : null;
+
+                case Weekly:
+                    // without any weekday it repeats on the weekday it was completed on
+                    var weekdays = Weekdays;
+                    return Enumerable.Range(1, 7)
+                        .Select(offset => (DateTime?)after.AddDays(offset))
+                        .FirstOrDefault(date => weekdays.Length == 0
+                            ? date!.Value.DayOfWeek == after.DayOfWeek
+                            : weekdays.Contains(DaysFromMonday(date!.Value)));
+
+                case Monthly:
+                    if (Day is < 1 or > 31)
+                        return null;
+
+                    var thisMonth = DayOfMonth(after.Year, after.Month, Day);
+                    if (thisMonth > after)
+                        return thisMonth;
+
+                    var nextMonth = new DateTime(after.Year, after.Month, 1).AddMonths(1);
+                    return DayOfMonth(nextMonth.Year, nextMonth.Month, Day);
+
+                default:
+                    return null;
+            }
+        }
+
+        private static int DaysFromMonday(DateTime date) => ((int)date.DayOfWeek + 6) % 7;
+
+        /// <summary>
+        /// The given day of the month, or its last day if the month is shorter.
+        /// </summary>
+        private static DateTime DayOfMonth(int year, int month, int day) =>
+            new(year, month, Math.Min(day, DateTime.DaysInMonth(year, month)));
+    }
+}
@@ -3,6 +3,8 @@
     public enum StatusCode
     {
         Ok,
-        Error
+        Error,
+        NotFound,
+        Forbidden
     }
 }
\ No newline at end of file
@@ -0,0 +1,6 @@
+﻿namespace TodoAPI_MVC.Models
+{
+    public record struct Subtask(
+        string Title,
+        bool Completed);
+}
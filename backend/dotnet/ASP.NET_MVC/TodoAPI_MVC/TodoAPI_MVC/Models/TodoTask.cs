@@ -1,4 +1,5 @@
-﻿using System.Text.Json.Serialization;
+﻿using System.Text.Json;
+using System.Text.Json.Serialization;
 using TodoAPI_MVC.Atributtes;
 using TodoAPI_MVC.Json;
 
@@ -15,6 +16,11 @@ namespace TodoAPI_MVC.Models
         DateTime? CreatedAt,
         [property: JsonConverter(typeof(DateTimeJsonConverterFactory))]
         DateTime? CompletedAt,
+        [property: JsonConverter(typeof(DateTimeJsonConverterFactory))]
+        DateTime? DueAt,
+        string[]? Tags,
+        Subtask[]? Subtasks,
+        JsonElement? Recurrence,
         int UserId)
     {
         private const int MinTitleLenght = 3;
@@ -26,5 +32,33 @@ namespace TodoAPI_MVC.Models
 
             return null;
         }
+
+        /// <summary>
+        /// The instance that follows this recurring task once it's completed at <paramref name="completedAt"/>,
+        /// due on the next day the rule allows at the time of day this one was due.
+        /// Null if the task doesn't recur or its rule has ended.
+        /// </summary>
+        /// <param name="utcOffset">The offset of the user's time zone, the rule works on the days as they see them.</param>
+        public TodoTask? NextOccurrence(DateTime completedAt, TimeSpan utcOffset)
+        {
+            if (RecurrenceRule.Parse(Recurrence) is not RecurrenceRule rule)
+                return null;
+
+            DateTime ToUserTime(DateTime dateTime) => dateTime.ToUniversalTime() + utcOffset;
+
+            var userCompletedAt = ToUserTime(completedAt);
+            var time = (DueAt is DateTime dueAt ? ToUserTime(dueAt) : userCompletedAt).TimeOfDay;
+            if (rule.NextOccurrence(userCompletedAt, time) is not DateTime next)
+                return null;
+
+            return this with
+            {
+                Id = default,
+                CreatedAt = null,
+                CompletedAt = null,
+                DueAt = DateTime.SpecifyKind(next - utcOffset, DateTimeKind.Utc).ToLocalTime(),
+                Subtasks = Subtasks?.Select(s => s with { Completed = false }).ToArray(),
+            };
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,9 @@
+﻿namespace TodoAPI_MVC.Models
+{
+    /// <summary>
+    /// A task toggled completed, along with the next instance created by completing a recurring task.
+    /// </summary>
+    public record struct ToggledTask(
+        TodoTask Task,
+        TodoTask? Next);
+}
@@ -1,6 +1,8 @@
 ﻿using Microsoft.AspNetCore.Identity;
 using Microsoft.AspNetCore.Mvc;
+using Microsoft.Net.Http.Headers;
 using System.Text.Json;
+using TodoAPI_MVC.Controllers;
 using TodoAPI_MVC.Database;
 using TodoAPI_MVC.Extensions;
 using TodoAPI_MVC.Json;
@@ -36,7 +38,8 @@ namespace TodoAPI_MVC
             var builder = WebApplication.CreateBuilder(args);
 
             builder.Services.AddCors(o => o.AddDefaultPolicy(p =>
-                p.WithMethods("*").WithHeaders("*").WithOrigins("*")));
+                p.WithMethods("*").WithHeaders("*").WithOrigins("*")
+                    .WithExposedHeaders(HeaderNames.ETag, ApiControllerBase.ErrorCodeHeader)));
 
             builder.AddJwtAuthentication(jsonSerializerOptions, variables);
             builder.Services.AddDbServiceOptions(
@@ -45,6 +48,7 @@ namespace TodoAPI_MVC
             builder.Services.AddDatabaseContext(variables);
             builder.Services.AddSingleton<IVariables>(variables);
             builder.Services.AddSingleton<IDefaults, Defaults>();
+            builder.Services.AddSingleton<ITaskEvents, TaskEvents>();
             builder.Services.AddScoped<DatabaseInitializor>();
 
             builder.Services.AddIdentityCore<User>()
@@ -0,0 +1,56 @@
+﻿using System.Collections.Concurrent;
+using System.Threading.Channels;
+
+namespace TodoAPI_MVC.Services
+{
+    public record struct TaskEvent(string Name, object Data)
+    {
+        public const string Created = "created";
+        public const string Updated = "updated";
+        public const string Deleted = "deleted";
+    }
+
+    public interface ITaskEvents
+    {
+        void Publish(int userId, TaskEvent taskEvent);
+        ChannelReader<TaskEvent> Subscribe(int userId, CancellationToken cancellationToken);
+    }
+
+    /// <summary>
+    /// Fans the changes made to a user's tasks out to every event stream the user has open.
+    /// </summary>
+    public class TaskEvents : ITaskEvents
+    {
+        private const int StreamCapacity = 64;
+        private readonly ConcurrentDictionary<int, ConcurrentDictionary<Channel<TaskEvent>, byte>> _streams = new();
+
+        public void Publish(int userId, TaskEvent taskEvent)
+        {
+            if (!_streams.TryGetValue(userId, out var streams))
+                return;
+
+            foreach (var stream in streams.Keys)
+                stream.Writer.TryWrite(taskEvent);
+        }
+
+        /// <summary>
+        /// Opens a stream of the user's events that ends once <paramref name="cancellationToken"/> is cancelled.
+        /// </summary>
+        public ChannelReader<TaskEvent> Subscribe(int userId, CancellationToken cancellationToken)
+        {
+            // a client that can't keep up loses its oldest events rather than holding the others back
+            var stream = Channel.CreateBounded<TaskEvent>(
+                new BoundedChannelOptions(StreamCapacity) { FullMode = BoundedChannelFullMode.DropOldest });
+
+            var streams = _streams.GetOrAdd(userId, _ => new());
+            streams[stream] = default;
+            cancellationToken.Register(() =>
+            {
+                streams.TryRemove(stream, out _);
+                stream.Writer.TryComplete();
+            });
+
+            return stream.Reader;
+        }
+    }
+}
@@ -0,0 +1,46 @@
+﻿using TodoAPI_MVC.Authentication;
+
+namespace TodoAPI_MVC_Tests.Authentication
+{
+    public class StreamTicketsTests
+    {
+        [Test]
+        public void TryRedeem_ShouldReturnTheUser_OnIssuedTicket()
+        {
+            var tickets = new StreamTickets();
+            var ticket = tickets.Issue(7);
+
+            tickets.TryRedeem(ticket, out var userId).Should().BeTrue();
+            userId.Should().Be(7);
+        }
+
+        [Test]
+        public void TryRedeem_ShouldFail_OnRedeemedTicket()
+        {
+            var tickets = new StreamTickets();
+            var ticket = tickets.Issue(7);
+            tickets.TryRedeem(ticket, out _);
+
+            tickets.TryRedeem(ticket, out _).Should().BeFalse();
+        }
+
+        [TestCase(null)]
+        [TestCase("")]
+        [TestCase("not a ticket")]
+        public void TryRedeem_ShouldFail_OnUnknownTicket(string? ticket)
+        {
+            var tickets = new StreamTickets();
+            tickets.Issue(7);
+
+            tickets.TryRedeem(ticket, out _).Should().BeFalse();
+        }
+
+        [Test]
+        public void Issue_ShouldIssueDistinctTickets()
+        {
+            var tickets = new StreamTickets();
+
+            tickets.Issue(7).Should().NotBe(tickets.Issue(7));
+        }
+    }
+}
@@ -1,9 +1,11 @@
 ﻿using Microsoft.AspNetCore.Identity;
 using Microsoft.AspNetCore.Mvc;
 using System.Security.Claims;
+using TodoAPI_MVC.Authentication;
 using TodoAPI_MVC.Controllers;
 using TodoAPI_MVC.Database.Interfaces;
 using TodoAPI_MVC.Models;
+using TodoAPI_MVC.Services;
 
 namespace TodoAPI_MVC_Tests.Controllers
 {
@@ -55,6 +57,54 @@ namespace TodoAPI_MVC_Tests.Controllers
             actual.Value.Should().BeEquivalentTo(TodoTask);
         }
 
+        [Test]
+        public async Task Get_ShouldReturnNotFound_OnMissingTask()
+        {
+            var controller = GetController(
+                taskDataMock: GetTaskDataMock(StatusCode.NotFound));
+
+            var actual = (ObjectResult)await controller.Get(1, CancellationToken.None);
+
+            actual.StatusCode.Should().Be(404);
+            actual.Value.Should().BeEquivalentTo(TestHelper.Error);
+        }
+
+        [Test]
+        public async Task Get_ShouldReturnForbidden_OnTaskOfAnotherUser()
+        {
+            var controller = GetController(
+                taskDataMock: GetTaskDataMock(StatusCode.Forbidden));
+
+            var actual = (ObjectResult)await controller.Get(1, CancellationToken.None);
+
+            actual.StatusCode.Should().Be(403);
+            actual.Value.Should().BeEquivalentTo(TestHelper.Error);
+            controller.Response.Headers[ApiControllerBase.ErrorCodeHeader].ToString()
+                .Should().Be(ApiControllerBase.NotOwnedErrorCode);
+        }
+
+        [Test]
+        public async Task Get_ShouldNotMarkOtherErrors_AsNotOwned()
+        {
+            var controller = GetController(
+                taskDataMock: GetTaskDataMock(StatusCode.NotFound));
+
+            await controller.Get(1, CancellationToken.None);
+
+            controller.Response.Headers.ContainsKey(ApiControllerBase.ErrorCodeHeader).Should().BeFalse();
+        }
+
+        [Test]
+        public async Task Delete_ShouldReturnNotFound_OnMissingTask()
+        {
+            var controller = GetController(
+                taskDataMock: GetTaskDataMock(StatusCode.NotFound));
+
+            var actual = (ObjectResult)await controller.Delete(1, CancellationToken.None);
+
+            actual.StatusCode.Should().Be(404);
+        }
+
         [Test]
         public async Task Get_ShouldFail_OnInvalidDatabaseResult()
         {
@@ -91,6 +141,43 @@ namespace TodoAPI_MVC_Tests.Controllers
             actual.Value.Should().BeEquivalentTo(new[] { TodoTask });
         }
 
+        [Test]
+        public async Task GetAllOwned_ShouldSendETag_OnValidUser()
+        {
+            var controller = GetController(
+                claims: new[] { new Claim("Id", $"{TodoTask.Id}") });
+
+            await controller.GetAllOwned(CancellationToken.None);
+
+            controller.Response.Headers.ETag.ToString().Should().MatchRegex("^\"[0-9A-F]+\"$");
+        }
+
+        [Test]
+        public async Task GetAllOwned_ShouldBeNotModified_OnMatchingETag()
+        {
+            var controller = GetController(
+                claims: new[] { new Claim("Id", $"{TodoTask.Id}") });
+            await controller.GetAllOwned(CancellationToken.None);
+            controller.Request.Headers.IfNoneMatch = controller.Response.Headers.ETag;
+
+            var actual = (StatusCodeResult)await controller.GetAllOwned(CancellationToken.None);
+
+            actual.StatusCode.Should().Be(304);
+        }
+
+        [Test]
+        public async Task GetAllOwned_ShouldSucceed_OnOutdatedETag()
+        {
+            var controller = GetController(
+                claims: new[] { new Claim("Id", $"{TodoTask.Id}") });
+            controller.Request.Headers.IfNoneMatch = "\"0\"";
+
+            var actual = (ObjectResult)await controller.GetAllOwned(CancellationToken.None);
+
+            actual.StatusCode.Should().BeInRange(200, 299);
+            actual.Value.Should().BeEquivalentTo(new[] { TodoTask });
+        }
+
         [Test]
         public async Task Update_ShouldSucceed_OnValidUser()
         {
@@ -109,7 +196,7 @@ namespace TodoAPI_MVC_Tests.Controllers
             var controller = GetController(
                 claims: new[] { new Claim("Id", $"{TodoTask.Id}") });
 
-            var actual = (ObjectResult)await controller.ToggleCompleted(1, CancellationToken.None);
+            var actual = (ObjectResult)await controller.ToggleCompleted(1, 0, CancellationToken.None);
 
             actual.StatusCode.Should().BeInRange(200, 299);
             actual.Value.Should().BeEquivalentTo(TodoTask);
@@ -163,17 +250,116 @@ namespace TodoAPI_MVC_Tests.Controllers
             actual.Value.Should().BeEquivalentTo(new[] { TodoTask });
         }
 
+        [Test]
+        public async Task Create_ShouldPublishEvent_OnValidUser()
+        {
+            var taskEventsMock = new Mock<ITaskEvents>();
+            var controller = GetController(
+                taskEventsMock: taskEventsMock,
+                claims: new[] { new Claim("Id", $"{TodoTask.Id}") });
+
+            await controller.Create(TodoTask, false, CancellationToken.None);
+
+            taskEventsMock.Verify(m => m.Publish(
+                TodoTask.UserId, new TaskEvent(TaskEvent.Created, TodoTask)), Times.Once);
+        }
+
+        [Test]
+        public async Task ToggleCompleted_ShouldPublishOnlyUpdatedEvent_OnTaskWithoutRecurrence()
+        {
+            var taskEventsMock = new Mock<ITaskEvents>();
+            var controller = GetController(
+                taskEventsMock: taskEventsMock,
+                claims: new[] { new Claim("Id", $"{TodoTask.Id}") });
+
+            await controller.ToggleCompleted(1, 0, CancellationToken.None);
+
+            taskEventsMock.Verify(m => m.Publish(
+                TodoTask.UserId, new TaskEvent(TaskEvent.Updated, TodoTask)), Times.Once);
+            taskEventsMock.Verify(m => m.Publish(
+                It.IsAny<int>(), It.Is<TaskEvent>(e => e.Name == TaskEvent.Created)), Times.Never);
+        }
+
+        [Test]
+        public async Task ToggleCompleted_ShouldPublishCreatedEvent_OnRecurringTask()
+        {
+            var next = TodoTask with { Id = 2, CreatedAt = null };
+            var taskDataMock = GetTaskDataMock();
+            taskDataMock.Setup(m => m.ToggleCompletedAsync(
+                    It.IsAny<int>(), It.IsAny<int?>(), It.IsAny<TimeSpan>(), It.IsAny<CancellationToken>()))
+                .ReturnsAsync(TestHelper.GetDbResult(StatusCode.Ok, false, new ToggledTask(TodoTask, next)));
+            var taskEventsMock = new Mock<ITaskEvents>();
+            var controller = GetController(
+                taskDataMock: taskDataMock,
+                taskEventsMock: taskEventsMock,
+                claims: new[] { new Claim("Id", $"{TodoTask.Id}") });
+
+            var actual = (ObjectResult)await controller.ToggleCompleted(1, 0, CancellationToken.None);
+
+            actual.Value.Should().BeEquivalentTo(TodoTask);
+            taskEventsMock.Verify(m => m.Publish(
+                TodoTask.UserId, new TaskEvent(TaskEvent.Updated, TodoTask)), Times.Once);
+            taskEventsMock.Verify(m => m.Publish(
+                TodoTask.UserId, new TaskEvent(TaskEvent.Created, next)), Times.Once);
+        }
+
+        [Test]
+        public async Task Update_ShouldNotPublishEvent_OnInvalidDatabaseResult()
+        {
+            var taskEventsMock = new Mock<ITaskEvents>();
+            var controller = GetController(
+                taskDataMock: GetTaskDataMock(StatusCode.Error),
+                taskEventsMock: taskEventsMock,
+                claims: new[] { new Claim("Id", $"{TodoTask.Id}") });
+
+            await controller.Update(1, TodoTask, CancellationToken.None);
+
+            taskEventsMock.Verify(m => m.Publish(It.IsAny<int>(), It.IsAny<TaskEvent>()), Times.Never);
+        }
+
+        [Test]
+        public async Task EventsTicket_ShouldOpenEvents_OnValidUser()
+        {
+            var streamTickets = new StreamTickets();
+            var controller = GetController(
+                streamTickets: streamTickets,
+                claims: new[] { new Claim("Id", $"{TodoTask.Id}") });
+
+            var actual = (ObjectResult)await controller.EventsTicket();
+
+            actual.StatusCode.Should().BeInRange(200, 299);
+            var ticket = (string)actual.Value!.GetType().GetProperty("Ticket")!.GetValue(actual.Value)!;
+            streamTickets.TryRedeem(ticket, out var userId).Should().BeTrue();
+            userId.Should().Be(TodoTask.UserId);
+        }
+
+        [Test]
+        public async Task Events_ShouldFail_OnUnknownTicket()
+        {
+            var controller = GetController();
+
+            var actual = await controller.Events("not a ticket", CancellationToken.None);
+
+            actual.Should().BeOfType<UnauthorizedResult>();
+        }
+
         private static TasksController GetController(
             Mock<ITaskData>? taskDataMock = null,
+            Mock<ITaskEvents>? taskEventsMock = null,
+            IStreamTickets? streamTickets = null,
             Mock<UserManager<User>>? userManagerMock = null,
             Mock<SignInManager<User>>? signInManagerMock = null,
             IEnumerable<Claim>? claims = null)
         {
             taskDataMock ??= GetTaskDataMock();
+            taskEventsMock ??= new Mock<ITaskEvents>();
+            streamTickets ??= new StreamTickets();
             userManagerMock ??= TestHelper.GetUserManagerMock();
             signInManagerMock ??= TestHelper.GetSignInManagerMock(userManagerMock.Object);
             return new TasksController(
                             taskDataMock.Object,
+                            taskEventsMock.Object,
+                            streamTickets,
                             userManagerMock.Object,
                             signInManagerMock.Object)
             {
@@ -203,8 +389,8 @@ namespace TodoAPI_MVC_Tests.Controllers
                 .ReturnsAsync(TestHelper.GetDbResult(returnedStatus, isErrorNull, TodoTask));
 
             mock.Setup(m => m.ToggleCompletedAsync(
-                    It.IsAny<int>(), It.IsAny<int?>(), It.IsAny<CancellationToken>()))
-                .ReturnsAsync(TestHelper.GetDbResult(returnedStatus, isErrorNull, TodoTask));
+                    It.IsAny<int>(), It.IsAny<int?>(), It.IsAny<TimeSpan>(), It.IsAny<CancellationToken>()))
+                .ReturnsAsync(TestHelper.GetDbResult(returnedStatus, isErrorNull, new ToggledTask(TodoTask, null)));
 
             mock.Setup(m => m.DeleteAsync(
                     It.IsAny<int>(), It.IsAny<int?>(), It.IsAny<CancellationToken>()))
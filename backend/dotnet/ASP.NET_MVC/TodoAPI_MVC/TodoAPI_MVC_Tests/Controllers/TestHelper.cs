@@ -22,10 +22,17 @@ namespace TodoAPI_MVC_Tests.Controllers
             userMock
                 .SetupGet(m => m.Identity)
                 .Returns(identityMock.Object);
+            var defaultHttpContext = new DefaultHttpContext();
             var httpContextMock = new Mock<HttpContext>();
             httpContextMock
                 .SetupGet(m => m.User)
                 .Returns(userMock.Object);
+            httpContextMock
+                .SetupGet(m => m.Request)
+                .Returns(defaultHttpContext.Request);
+            httpContextMock
+                .SetupGet(m => m.Response)
+                .Returns(defaultHttpContext.Response);
 
             return new ControllerContext
             {
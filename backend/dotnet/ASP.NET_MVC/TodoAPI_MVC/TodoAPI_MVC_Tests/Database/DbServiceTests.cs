@@ -0,0 +1,38 @@
+﻿using System.Text.Json;
+using TodoAPI_MVC.Database.Service;
+
+namespace TodoAPI_MVC_Tests.Database
+{
+    public class DbServiceTests
+    {
+        [TestCase("plain", "'plain'")]
+        [TestCase("it's", "'it''s'")]
+        [TestCase("'; DROP TABLE tasks; --", "'''; DROP TABLE tasks; --'")]
+        public void GetSqlValue_ShouldEscapeQuotes_OnStrings(string value, string expected)
+        {
+            new DbService().GetSqlValue(value).Should().Be(expected);
+        }
+
+        [Test]
+        public void GetSqlValue_ShouldEscapeQuotes_OnChars()
+        {
+            new DbService().GetSqlValue('\'').Should().Be("''''");
+        }
+
+        [Test]
+        public void GetSqlValue_ShouldEscapeQuotes_OnTags()
+        {
+            var tags = new[] { "home", "o'clock" };
+
+            new DbService().GetSqlValue(tags).Should().Be("ARRAY['home', 'o''clock']::TEXT[]");
+        }
+
+        [Test]
+        public void GetSqlValue_ShouldEscapeQuotes_OnJson()
+        {
+            var json = JsonDocument.Parse("{\"until\": \"it's done\"}").RootElement;
+
+            new DbService().GetSqlValue(json).Should().Be("'{\"until\": \"it''s done\"}'::JSONB");
+        }
+    }
+}
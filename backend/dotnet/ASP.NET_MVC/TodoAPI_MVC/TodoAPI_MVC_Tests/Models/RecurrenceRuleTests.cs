@@ -0,0 +1,77 @@
+﻿using System.Globalization;
+using System.Text.Json;
+using TodoAPI_MVC.Models;
+
+namespace TodoAPI_MVC_Tests.Models
+{
+    public class RecurrenceRuleTests
+    {
+        private const string FixturesPath = "Fixtures/recurrence.json";
+
+        /// <summary>
+        /// Cases shared with the frontend tests, so both engines agree on when a task recurs.
+        /// </summary>
+        private static IEnumerable<TestCaseData> Fixtures()
+        {
+            var path = Path.Combine(TestContext.CurrentContext.TestDirectory, FixturesPath);
+            using var fixtures = JsonDocument.Parse(File.ReadAllText(path));
+
+            foreach (var fixture in fixtures.RootElement.EnumerateArray())
+            {
+                var next = fixture.GetProperty("next");
+                yield return new TestCaseData(
+                    fixture.GetProperty("rule").Clone(),
+                    DateTime.Parse(fixture.GetProperty("completed_at").GetString()!, CultureInfo.InvariantCulture),
+                    TimeSpan.Parse(fixture.GetProperty("time").GetString()!, CultureInfo.InvariantCulture),
+                    next.ValueKind == JsonValueKind.Null
+                        ? null
+                        : DateTime.Parse(next.GetString()!, CultureInfo.InvariantCulture))
+                    .SetArgDisplayNames(fixture.GetProperty("name").GetString());
+            }
+        }
+
+        [TestCaseSource(nameof(Fixtures))]
+        public void NextOccurrence_ShouldMatchTheFixture(
+            JsonElement json, DateTime completedAt, TimeSpan time, DateTime? expected)
+        {
+            var rule = RecurrenceRule.Parse(json);
+
+            rule.Should().NotBeNull();
+            rule!.Value.NextOccurrence(completedAt, time).Should().Be(expected);
+        }
+
+        [TestCase("null")]
+        [TestCase("{}")]
+        [TestCase("{\"frequency\": \"daily\"}")]
+        public void Parse_ShouldFail_OnInvalidRule(string json)
+        {
+            RecurrenceRule.Parse(JsonDocument.Parse(json).RootElement).Should().BeNull();
+        }
+
+        [Test]
+        public void TodoTask_NextOccurrence_ShouldResetTheTask_OnTheDayTheUserSees()
+        {
+            var task = new TodoTask(
+                7, "Water the plants", Priority.B, null,
+                new DateTime(2023, 3, 1, 8, 0, 0, DateTimeKind.Utc),
+                null,
+                new DateTime(2023, 3, 14, 7, 0, 0, DateTimeKind.Utc),
+                new[] { "home" },
+                new[] { new Subtask("Balcony", true) },
+                JsonDocument.Parse("{\"frequency\": {\"kind\": \"daily\"}}").RootElement,
+                1);
+
+            // 23:30 UTC is already the 15th for a user two hours ahead, who had it due at 9:00
+            var next = task.NextOccurrence(
+                new DateTime(2023, 3, 14, 23, 30, 0, DateTimeKind.Utc), TimeSpan.FromHours(2));
+
+            next.Should().NotBeNull();
+            next!.Value.Id.Should().Be(0);
+            next.Value.CreatedAt.Should().BeNull();
+            next.Value.CompletedAt.Should().BeNull();
+            next.Value.DueAt!.Value.ToUniversalTime().Should().Be(new DateTime(2023, 3, 16, 7, 0, 0, DateTimeKind.Utc));
+            next.Value.Subtasks.Should().BeEquivalentTo(new[] { new Subtask("Balcony", false) });
+            next.Value.Recurrence.Should().NotBeNull();
+        }
+    }
+}
@@ -0,0 +1,49 @@
+﻿using TodoAPI_MVC.Services;
+
+namespace TodoAPI_MVC_Tests.Services
+{
+    public class TaskEventsTests
+    {
+        private static readonly TaskEvent Deleted = new(TaskEvent.Deleted, new { Id = 1 });
+
+        [Test]
+        public void Publish_ShouldReachEveryStreamOfTheUser()
+        {
+            var taskEvents = new TaskEvents();
+            var first = taskEvents.Subscribe(1, CancellationToken.None);
+            var second = taskEvents.Subscribe(1, CancellationToken.None);
+
+            taskEvents.Publish(1, Deleted);
+
+            first.TryRead(out var firstEvent).Should().BeTrue();
+            firstEvent.Should().Be(Deleted);
+            second.TryRead(out var secondEvent).Should().BeTrue();
+            secondEvent.Should().Be(Deleted);
+        }
+
+        [Test]
+        public void Publish_ShouldNotReachOtherUsers()
+        {
+            var taskEvents = new TaskEvents();
+            var stream = taskEvents.Subscribe(2, CancellationToken.None);
+
+            taskEvents.Publish(1, Deleted);
+
+            stream.TryRead(out _).Should().BeFalse();
+        }
+
+        [Test]
+        public void Subscribe_ShouldEndTheStream_OnCancellation()
+        {
+            var taskEvents = new TaskEvents();
+            using var cancellation = new CancellationTokenSource();
+            var stream = taskEvents.Subscribe(1, cancellation.Token);
+
+            cancellation.Cancel();
+            taskEvents.Publish(1, Deleted);
+
+            stream.Completion.IsCompleted.Should().BeTrue();
+            stream.TryRead(out _).Should().BeFalse();
+        }
+    }
+}
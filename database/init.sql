@@ -11,8 +11,19 @@ CREATE TABLE IF NOT EXISTS tasks (
   priority      VARCHAR(4) DEFAULT NULL,
   title         VARCHAR(255) NOT NULL,
   completed_at  TIMESTAMP DEFAULT NULL,
+  due_at        TIMESTAMP DEFAULT NULL,
+  tags          TEXT[] DEFAULT NULL,
+  subtasks      JSONB DEFAULT NULL,
+  recurrence    JSONB DEFAULT NULL,
   created_at    TIMESTAMP NOT NULL DEFAULT current_timestamp,
   description   TEXT DEFAULT NULL,
   user_id       INTEGER NOT NULL,
   CONSTRAINT fk_users FOREIGN KEY (user_id) REFERENCES users(id)
 );
+
+-- Databases created before these columns existed get them added,
+-- the script is safe to run again against an existing database.
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS due_at TIMESTAMP DEFAULT NULL;
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS tags TEXT[] DEFAULT NULL;
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS subtasks JSONB DEFAULT NULL;
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS recurrence JSONB DEFAULT NULL;
@@ -1,126 +1,295 @@
-use std::fmt::Display;
+use std::{cell::Cell, fmt::Display, rc::Rc};
 
-use gloo::console::log;
-use serde::{Deserialize, Serialize, de::DeserializeOwned};
-use reqwasm::{http::*, Error};
-use lazy_static::lazy_static;
+use gloo::{
+    console::log,
+    events::EventListener,
+    timers::{callback::Timeout, future::TimeoutFuture},
+};
+use serde::de::DeserializeOwned;
+use reqwasm::http::*;
 use wasm_bindgen::JsValue;
+use web_sys::AbortController;
 
-const API_CONFIG_RAW_JSON: &str = include_str!("api_settings.json");
+use super::{api_config::ApiConfig, api_error_response::ApiErrorResponse, request_options::RequestOptions};
 
-lazy_static! {
-    static ref API_CONFIG: ApiConfig = serde_json::from_str(API_CONFIG_RAW_JSON).unwrap();
-}
+const ERROR_CODE_HEADER: &str = "x-error-code";
+/// The error code of a request for a task that belongs to another user.
+pub const NOT_OWNED_ERROR_CODE: &str = "not-owned";
 
-#[derive(Serialize, Deserialize)]
-struct ApiConfig {
-    api_uri: String
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestInfo {
+    pub method: String,
+    pub uri: String,
 }
 
-pub enum ApiError {
-    HttpStatus(u16, String),
-    Parse(String),
-    Other(String)
+impl RequestInfo {
+    fn new(method: &Method, uri: &str) -> Self {
+        Self {
+            method: method.to_string(),
+            uri: uri.to_string(),
+        }
+    }
 }
 
-impl Display for ApiError {
+impl Display for RequestInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+        write!(f, "{} {}", self.method, self.uri)
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiErrorKind {
+    /// The server responded with a non-success status code,
+    /// `message` holds the `error` from the response body if there was one
+    /// and `code` the `X-Error-Code` header, which tells apart errors sharing a status.
+    Http {
+        status: u16,
+        status_text: String,
+        message: Option<String>,
+        code: Option<String>,
+        retry_after_ms: Option<u32>,
+    },
+    /// The request never got a response, e.g. the server is unreachable.
+    Network(String),
+    /// The request took longer than allowed.
+    Timeout,
+    /// The request was cancelled through its abort signal.
+    Aborted,
+    /// The response was successful, but its body was not what we expected.
+    Deserialize(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiError {
+    pub kind: ApiErrorKind,
+    pub request: RequestInfo,
+}
+
 impl ApiError {
-    pub fn to_string(&self) -> String {
-        match self {
-            ApiError::HttpStatus(code, message) => format!("{}: {}", code, message),
-            ApiError::Parse(error) => error.to_owned(),
-            ApiError::Other(error) => error.to_owned(),
+    pub fn new(kind: ApiErrorKind, request: RequestInfo) -> Self {
+        Self { kind, request }
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        match &self.kind {
+            ApiErrorKind::Http { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        match &self.kind {
+            ApiErrorKind::Http { message, .. } => message.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        match &self.kind {
+            ApiErrorKind::Http { code, .. } => code.as_deref(),
+            _ => None,
         }
     }
 }
 
+impl Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ApiErrorKind::Http { message: Some(message), .. } => write!(f, "{}", message),
+            ApiErrorKind::Http { status, status_text, message: None, .. } => write!(f, "{}: {}", status, status_text),
+            ApiErrorKind::Network(error) => write!(f, "Unable to reach the server: {}", error),
+            ApiErrorKind::Timeout => write!(f, "The server took too long to respond"),
+            ApiErrorKind::Aborted => write!(f, "The request was cancelled"),
+            ApiErrorKind::Deserialize(error) => write!(f, "Unexpected response from the server: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Response to a request sent with `If-None-Match`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conditional<T> {
+    Modified { data: T, etag: Option<String> },
+    NotModified,
+}
+
 pub struct ApiClient;
 
 impl ApiClient {
-    pub async fn send_text(uri: &str, method: Method, body: Option<impl Into<JsValue>>, headers: Option<impl Into<Headers>>) -> Result<String, ApiError> {
-        let mut request = Request::new(&format!("{}{}", API_CONFIG.api_uri, uri)).method(method);
-            
-        if let Some(body) = body {
-            request = request.body(body);
-        }
+    pub async fn send_text(uri: &str, method: Method, body: Option<impl Into<JsValue>>, headers: Option<impl Into<Headers>>, options: RequestOptions) -> Result<String, ApiError> {
+        let request_info = RequestInfo::new(&method, uri);
+        let response = Self::send::<ApiErrorResponse>(uri, method, body, headers, &options, &request_info).await?;
+
+        return match response.text().await {
+            Ok(text) => Ok(text),
+            Err(error) => Err(ApiError::new(ApiErrorKind::Deserialize(error.to_string()), request_info)),
+        };
+    }
+
+    pub async fn send_json<T, E>(uri: &str, method: Method, body: Option<impl Into<JsValue>>, headers: Option<impl Into<Headers>>, options: RequestOptions) -> Result<T, ApiError>
+    where
+        T: DeserializeOwned,
+        E: DeserializeOwned + Display {
+        let request_info = RequestInfo::new(&method, uri);
+        let response = Self::send::<E>(uri, method, body, headers, &options, &request_info).await?;
 
-        if let Some(headers) = headers {
-            request = request.headers(headers.into());
+        return Self::parse_response::<T>(response, request_info).await;
+    }
+
+    /// Sends `etag` as `If-None-Match`, so an unchanged resource comes back as `NotModified` without a body.
+    pub async fn send_json_conditional<T, E>(uri: &str, method: Method, body: Option<impl Into<JsValue>>, headers: Headers, etag: Option<&str>, options: RequestOptions) -> Result<Conditional<T>, ApiError>
+    where
+        T: DeserializeOwned,
+        E: DeserializeOwned + Display {
+        if let Some(etag) = etag {
+            headers.append("if-none-match", etag);
         }
+        let request_info = RequestInfo::new(&method, uri);
+        let response = Self::send::<E>(uri, method, body, Some(headers), &options, &request_info).await?;
 
-        let response = request.send().await;
+        if response.status() == 304 {
+            return Ok(Conditional::NotModified);
+        }
+        // cross-origin responses only carry the ETag if the server exposes it
+        let etag = response.headers().get("etag");
+        return match Self::parse_response::<T>(response, request_info).await {
+            Ok(data) => Ok(Conditional::Modified { data, etag }),
+            Err(error) => Err(error),
+        };
+    }
 
-        return match Self::ensure_success_status_code(&response) {
-            Some(error) => Err(error),
-            None => Ok(response.unwrap().text().await.unwrap())
+    async fn send<E>(uri: &str, method: Method, body: Option<impl Into<JsValue>>, headers: Option<impl Into<Headers>>, options: &RequestOptions, request_info: &RequestInfo) -> Result<Response, ApiError>
+    where
+        E: DeserializeOwned + Display {
+        let url = format!("{}{}", ApiConfig::get().api_uri, uri);
+        // body and headers are kept around, so every attempt can send them again
+        let body: Option<JsValue> = body.map(|body| body.into());
+        let headers: Vec<(String, String)> = match headers {
+            Some(headers) => headers.into().entries().collect(),
+            None => Vec::new(),
         };
+
+        let mut attempt = 1;
+        loop {
+            if options.is_aborted() {
+                return Err(ApiError::new(ApiErrorKind::Aborted, request_info.clone()));
+            }
+
+            let error = match Self::send_once::<E>(&url, method, body.clone(), &headers, options, request_info).await {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            match options.retry.next_delay_ms(&error, method, attempt) {
+                Some(delay_ms) => {
+                    log!(format!("{} failed on attempt {}, retrying in {}ms", request_info, attempt, delay_ms));
+                    TimeoutFuture::new(delay_ms).await;
+                    attempt += 1;
+                }
+                None => return Err(error),
+            }
+        }
     }
 
-    pub async fn send_json<T, E>(uri: &str, method: Method, body: Option<impl Into<JsValue>>, headers: Option<impl Into<Headers>>) -> Result<T, ApiError>
-    where 
-        T: DeserializeOwned,
-        E: Into<ApiError> + DeserializeOwned {
-        let mut request = Request::new(&format!("{}{}", API_CONFIG.api_uri, uri)).method(method);
-            
+    async fn send_once<E>(url: &str, method: Method, body: Option<JsValue>, headers: &[(String, String)], options: &RequestOptions, request_info: &RequestInfo) -> Result<Response, ApiError>
+    where
+        E: DeserializeOwned + Display {
+        // every attempt gets its own controller, aborted either by the timeout or the caller's signal
+        let controller = AbortController::new().ok();
+        let timed_out = Rc::new(Cell::new(false));
+        let _timeout = match (&controller, options.timeout_ms) {
+            (Some(controller), Some(timeout_ms)) => {
+                let controller = controller.clone();
+                let timed_out = timed_out.clone();
+                Some(Timeout::new(timeout_ms, move || {
+                    timed_out.set(true);
+                    controller.abort();
+                }))
+            }
+            _ => None,
+        };
+        let _abort_listener = match (&controller, &options.abort_signal) {
+            (Some(controller), Some(signal)) => {
+                let controller = controller.clone();
+                Some(EventListener::once(signal, "abort", move |_| controller.abort()))
+            }
+            _ => None,
+        };
+
+        let mut request = Request::new(url).method(method);
+
+        if let Some(controller) = &controller {
+            request = request.abort_signal(Some(&controller.signal()));
+        }
+
         if let Some(body) = body {
             request = request.body(body);
         }
 
-        if let Some(headers) = headers {
-            request = request.headers(headers.into());
+        if !headers.is_empty() {
+            let request_headers = Headers::new();
+            for (name, value) in headers {
+                request_headers.append(name, value);
+            }
+            request = request.headers(request_headers);
         }
 
-        let response = request.send().await;
-
-        let error = Self::ensure_success_status_code(&response);
-
-        return match error {
-            Some(error) => match response {
-                Ok(response) => Self::parse_response::<T, E>(response).await,
-                Err(_) => Err(error)
+        return match request.send().await {
+            Ok(response) => match response.status() {
+                // 304 only comes back for conditional requests, which handle it themselves
+                200..=299 | 304 => Ok(response),
+                _ => Err(Self::parse_error::<E>(response, request_info.clone()).await),
             },
-            None => Self::parse_response::<T, E>(response.unwrap()).await,
+            Err(_) if timed_out.get() => Err(ApiError::new(ApiErrorKind::Timeout, request_info.clone())),
+            Err(_) if options.is_aborted() => Err(ApiError::new(ApiErrorKind::Aborted, request_info.clone())),
+            Err(error) => Err(ApiError::new(ApiErrorKind::Network(error.to_string()), request_info.clone())),
         };
     }
 
-    fn ensure_success_status_code(response: &Result<Response, Error>) -> Option<ApiError> {
-        match response {
-            Ok(response) => 
-                match response.status() {
-                    200..=299 => None,
-                    _ => Some(ApiError::HttpStatus(response.status(), response.status_text()))
-                }
-            Err(error) => Some(ApiError::Other(error.to_string())),
-        }
+    async fn parse_response<T>(response: Response, request_info: RequestInfo) -> Result<T, ApiError>
+    where
+        T: DeserializeOwned {
+        let text = match response.text().await {
+            Ok(text) => text,
+            Err(error) => return Err(ApiError::new(ApiErrorKind::Deserialize(error.to_string()), request_info)),
+        };
+
+        return match serde_json::from_str::<T>(&text) {
+            Ok(ok) => Ok(ok),
+            Err(error) => {
+                log!(error.to_string());
+                Err(ApiError::new(ApiErrorKind::Deserialize(error.to_string()), request_info))
+            }
+        };
     }
 
-    async fn parse_response<T, E>(response: Response) -> Result<T, ApiError>
-    where 
-        T: DeserializeOwned,
-        E: Into<ApiError> + DeserializeOwned {
-            let response_clone = Response::from_raw(response.as_raw().clone().unwrap());
-            return match response_clone.json::<T>().await {
-                Ok(ok) => Ok(ok),
-                Err(err) => {
-                    log!(err.to_string());
-                    let response_clone = Response::from_raw(response.as_raw().clone().unwrap());
-                    match response_clone.json::<E>().await {
-                        Ok(ok) => Err(ok.into()),
-                        Err(err) => {
-                            log!(err.to_string());
-                            let response_clone = Response::from_raw(response.as_raw().clone().unwrap());
-                            let mut text = response_clone.text().await.unwrap_or_default();
-                            if text.trim().is_empty() {
-                                text.push_str("Unknown API error");
-                            }
-                            Err(ApiError::Other(text))
-                        }
-                }}
-            };
+    async fn parse_error<E>(response: Response, request_info: RequestInfo) -> ApiError
+    where
+        E: DeserializeOwned + Display {
+        let text = response.text().await.unwrap_or_default();
+        let message = match serde_json::from_str::<E>(&text) {
+            Ok(error) => Some(error.to_string()).filter(|message| !message.trim().is_empty()),
+            Err(_) if text.trim().is_empty() => None,
+            Err(_) => Some(text),
+        };
+        // only the delay-seconds form of Retry-After is supported
+        let retry_after_ms = response
+            .headers()
+            .get("retry-after")
+            .and_then(|value| value.trim().parse::<u32>().ok())
+            .map(|seconds| seconds.saturating_mul(1000));
+        let code = response.headers().get(ERROR_CODE_HEADER);
+
+        ApiError::new(
+            ApiErrorKind::Http {
+                status: response.status(),
+                status_text: response.status_text(),
+                message,
+                code,
+                retry_after_ms,
+            },
+            request_info,
+        )
     }
-}
\ No newline at end of file
+}
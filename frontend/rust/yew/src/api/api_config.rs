@@ -0,0 +1,140 @@
+use std::{fmt::Display, sync::OnceLock};
+
+use gloo::{console::log, utils::{document, window}};
+use reqwasm::http::Request;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+const API_CONFIG_RAW_JSON: &str = include_str!("api_settings.json");
+const API_CONFIG_META_NAME: &str = "todo-api-uri";
+const API_CONFIG_GLOBAL_NAME: &str = "__TODO_API_CONFIG__";
+const API_CONFIG_URI: &str = "/config.json";
+
+const DEFAULT_POLL_INTERVAL_SECS: u32 = 60;
+const DEFAULT_REMEMBER_DAYS: u32 = 30;
+
+static API_CONFIG: OnceLock<ApiConfig> = OnceLock::new();
+
+fn default_poll_interval_secs() -> u32 {
+    DEFAULT_POLL_INTERVAL_SECS
+}
+
+fn default_remember_days() -> u32 {
+    DEFAULT_REMEMBER_DAYS
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum ApiConfigSource {
+    Global,
+    Meta,
+    ConfigFile,
+    #[default]
+    Embedded,
+}
+
+impl Display for ApiConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiConfigSource::Global => write!(f, "window.{}", API_CONFIG_GLOBAL_NAME),
+            ApiConfigSource::Meta => write!(f, "<meta name=\"{}\">", API_CONFIG_META_NAME),
+            ApiConfigSource::ConfigFile => write!(f, "{}", API_CONFIG_URI),
+            ApiConfigSource::Embedded => write!(f, "api_settings.json"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ApiConfig {
+    pub api_uri: String,
+    /// Subscribes to the task event stream, for backends that provide one.
+    #[serde(default)]
+    pub live_updates: bool,
+    /// How often the task list is refreshed in the background, `0` turns it off.
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u32,
+    /// How long a login with "Remember me" is kept after the browser is closed.
+    #[serde(default = "default_remember_days")]
+    pub remember_days: u32,
+    /// Renews the session before it expires, for backends that provide `/users/renew`.
+    #[serde(default)]
+    pub session_renewal: bool,
+    #[serde(skip)]
+    pub source: ApiConfigSource,
+}
+
+impl ApiConfig {
+    /// Resolves the config once at startup, trying the window global, the meta tag
+    /// and `/config.json` in that order before falling back to the embedded settings.
+    pub async fn init() -> &'static ApiConfig {
+        if let Some(config) = API_CONFIG.get() {
+            return config;
+        }
+
+        let config = match Self::from_global().or_else(Self::from_meta) {
+            Some(config) => config,
+            None => match Self::from_config_file().await {
+                Some(config) => config,
+                None => Self::embedded(),
+            },
+        };
+
+        log!(format!("Using API at {} (from {})", config.api_uri, config.source));
+        API_CONFIG.get_or_init(|| config)
+    }
+
+    pub fn get() -> &'static ApiConfig {
+        API_CONFIG.get_or_init(Self::embedded)
+    }
+
+    fn embedded() -> Self {
+        let mut config: ApiConfig = serde_json::from_str(API_CONFIG_RAW_JSON).unwrap();
+        config.source = ApiConfigSource::Embedded;
+        config
+    }
+
+    fn from_global() -> Option<Self> {
+        let global = js_sys::Reflect::get(&window(), &JsValue::from_str(API_CONFIG_GLOBAL_NAME)).ok()?;
+        if global.is_undefined() || global.is_null() {
+            return None;
+        }
+
+        let json: String = js_sys::JSON::stringify(&global).ok()?.into();
+        let mut config = Self::parse(&json)?;
+        config.source = ApiConfigSource::Global;
+        Some(config)
+    }
+
+    fn from_meta() -> Option<Self> {
+        let selector = format!("meta[name=\"{}\"]", API_CONFIG_META_NAME);
+        let meta = document().query_selector(&selector).ok()??;
+        let api_uri = meta.get_attribute("content")?;
+        if api_uri.trim().is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            api_uri: api_uri.trim().to_string(),
+            source: ApiConfigSource::Meta,
+            ..Self::embedded()
+        })
+    }
+
+    async fn from_config_file() -> Option<Self> {
+        let response = Request::get(API_CONFIG_URI).send().await.ok()?;
+        if !(200..=299).contains(&response.status()) {
+            return None;
+        }
+
+        let text = response.text().await.ok()?;
+        let mut config = Self::parse(&text)?;
+        config.source = ApiConfigSource::ConfigFile;
+        Some(config)
+    }
+
+    fn parse(json: &str) -> Option<Self> {
+        match serde_json::from_str::<ApiConfig>(json) {
+            Ok(config) if !config.api_uri.trim().is_empty() => Some(config),
+            _ => None,
+        }
+    }
+}
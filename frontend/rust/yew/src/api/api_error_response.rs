@@ -1,14 +1,14 @@
-use serde::*;
+use std::fmt::Display;
 
-use super::api_client::ApiError;
+use serde::*;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ApiErrorResponse {
     pub error: String
 }
 
-impl Into<ApiError> for ApiErrorResponse {
-    fn into(self) -> ApiError {
-        ApiError::Other(self.error)
+impl Display for ApiErrorResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
     }
-}
\ No newline at end of file
+}
@@ -1,3 +1,5 @@
+use chrono::{DateTime, TimeZone, Utc};
+use gloo::utils::window;
 use serde::{Serialize, Deserialize};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -5,4 +7,38 @@ pub struct Auth {
     pub id: u32,
     pub username: String,
     pub token: String,
+    /// Sent by backends that tell when the token expires, otherwise it's read from the token.
+    #[serde(default)]
+    pub expires_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenClaims {
+    exp: i64,
+}
+
+impl Auth {
+    /// When the session ends, `None` if neither the response nor the token says.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        match &self.expires_at {
+            Some(expires_at) => DateTime::parse_from_rfc3339(expires_at)
+                .ok()
+                .map(|expires_at| expires_at.with_timezone(&Utc)),
+            None => token_expiry(&self.token),
+        }
+    }
+}
+
+/// Reads the `exp` claim of a JWT, the signature is the server's business.
+fn token_expiry(token: &str) -> Option<DateTime<Utc>> {
+    let payload = token.split('.').nth(1)?;
+    // the payload is base64url without padding, atob only understands plain base64
+    let mut base64 = payload.replace('-', "+").replace('_', "/");
+    while base64.len() % 4 != 0 {
+        base64.push('=');
+    }
+
+    let json = window().atob(&base64).ok()?;
+    let claims = serde_json::from_str::<TokenClaims>(&json).ok()?;
+    Utc.timestamp_opt(claims.exp, 0).single()
 }
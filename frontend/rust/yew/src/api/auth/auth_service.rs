@@ -1,13 +1,15 @@
 use reqwasm::http::{Method, Headers};
 use serde_json::json;
 
-use crate::api::{api_client::{ApiClient, ApiError}, api_error_response::ApiErrorResponse};
+use crate::api::{api_client::{ApiClient, ApiError}, api_error_response::ApiErrorResponse, request_options::{RequestOptions, RetryPolicy}};
 
 use super::{auth_response::AuthResponse, auth::Auth};
 
 pub struct AuthService;
 
 const LOGIN_URI: &str = "/users/login";
+const LOGOUT_URI: &str = "/users/logout";
+const RENEW_URI: &str = "/users/renew";
 const USERS_URI: &str = "/users";
 
 impl AuthService {
@@ -16,7 +18,8 @@ impl AuthService {
             LOGIN_URI,
             Method::POST,
         Some(AuthService::get_auth_body(username, password)),
-        Some(AuthService::get_headers())).await;
+        Some(AuthService::get_headers()),
+        RequestOptions::default()).await;
 
         return match response {
             Ok(ok) => Ok(ok.data),
@@ -30,6 +33,7 @@ impl AuthService {
             Method::POST,
             Some(AuthService::get_auth_body(username, password)),
             Some(AuthService::get_headers()),
+            RequestOptions::default(),
         )
         .await;
 
@@ -39,6 +43,42 @@ impl AuthService {
         }
     }
 
+    /// Trades a token that is about to expire for a fresh one, only for backends with `session_renewal`.
+    pub async fn renew(token: String) -> Result<Auth, ApiError> {
+        let body: Option<&str> = None;
+        let response = ApiClient::send_json::<AuthResponse, ApiErrorResponse>(
+            RENEW_URI,
+            Method::POST,
+            body,
+            Some(AuthService::get_token_headers(token)),
+            RequestOptions::default(),
+        )
+        .await;
+
+        return match response {
+            Ok(ok) => Ok(ok.data),
+            Err(error) => Err(error)
+        }
+    }
+
+    pub async fn logout(token: String) -> Result<(), ApiError> {
+        let body: Option<&str> = None;
+        let response = ApiClient::send_text(
+            LOGOUT_URI,
+            Method::POST,
+            body,
+            Some(AuthService::get_token_headers(token)),
+            // revoking the same token twice is harmless
+            RequestOptions::default().with_retry(RetryPolicy::default().allow_non_idempotent()),
+        )
+        .await;
+
+        return match response {
+            Ok(_) => Ok(()),
+            Err(error) => Err(error)
+        }
+    }
+
     fn get_auth_body(username: String, password: String) -> String {
         json! {
             {
@@ -53,6 +93,11 @@ impl AuthService {
         let headers = Headers::default();
         headers.append("content-type", "application/json");
         headers
-}
+    }
 
+    fn get_token_headers(token: String) -> Headers {
+        let headers = AuthService::get_headers();
+        headers.append("x-auth-token", &token);
+        headers
+    }
 }
@@ -1,4 +1,6 @@
+pub mod api_config;
 pub mod api_client;
 pub mod auth;
 pub mod api_error_response;
+pub mod request_options;
 pub mod tasks;
\ No newline at end of file
@@ -0,0 +1,237 @@
+use reqwasm::http::Method;
+use web_sys::AbortSignal;
+
+use super::api_client::{ApiError, ApiErrorKind};
+
+const DEFAULT_TIMEOUT_MS: u32 = 15000;
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BASE_DELAY_MS: u32 = 500;
+const DEFAULT_MAX_DELAY_MS: u32 = 8000;
+const DEFAULT_JITTER: f64 = 0.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestOptions {
+    pub retry: RetryPolicy,
+    /// Time limit for a single attempt, `None` waits indefinitely.
+    pub timeout_ms: Option<u32>,
+    /// Cancels the request, including any pending retries, once aborted.
+    pub abort_signal: Option<AbortSignal>,
+}
+
+impl Default for RequestOptions {
+    fn default() -> Self {
+        Self {
+            retry: RetryPolicy::default(),
+            timeout_ms: Some(DEFAULT_TIMEOUT_MS),
+            abort_signal: None,
+        }
+    }
+}
+
+impl RequestOptions {
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_timeout_ms(mut self, timeout_ms: Option<u32>) -> Self {
+        self.timeout_ms = timeout_ms;
+        self
+    }
+
+    pub fn with_abort_signal(mut self, abort_signal: Option<AbortSignal>) -> Self {
+        self.abort_signal = abort_signal;
+        self
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        match &self.abort_signal {
+            Some(signal) => signal.aborted(),
+            None => false,
+        }
+    }
+}
+
+/// Decides whether and when a failed request is sent again.
+/// Delays grow exponentially from `base_delay_ms` up to `max_delay_ms`,
+/// `jitter` is the fraction of each delay that is randomized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay_ms: u32,
+    pub max_delay_ms: u32,
+    pub jitter: f64,
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay_ms: DEFAULT_BASE_DELAY_MS,
+            max_delay_ms: DEFAULT_MAX_DELAY_MS,
+            jitter: DEFAULT_JITTER,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Allows retrying methods like POST or PATCH, that are not safe to repeat in general.
+    pub fn allow_non_idempotent(mut self) -> Self {
+        self.retry_non_idempotent = true;
+        self
+    }
+
+    /// Returns the delay before the next attempt, or `None` if the request should not be repeated.
+    pub fn next_delay_ms(&self, error: &ApiError, method: Method, attempt: u32) -> Option<u32> {
+        self.retry_delay_ms(error, method, attempt, js_sys::Math::random())
+    }
+
+    /// Exponential delay before the given attempt, with jitter applied.
+    pub fn backoff_delay_ms(&self, attempt: u32) -> u32 {
+        self.jittered_delay_ms(attempt, js_sys::Math::random())
+    }
+
+    /// `next_delay_ms` with the random number in `[0, 1)` that picks the jittered part of the delay.
+    /// A delay the server asked for with `Retry-After` is used as is, unless it's longer than `max_delay_ms`.
+    fn retry_delay_ms(&self, error: &ApiError, method: Method, attempt: u32, random: f64) -> Option<u32> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        if !self.retry_non_idempotent && !Self::is_idempotent(method) {
+            return None;
+        }
+
+        match &error.kind {
+            ApiErrorKind::Network(_) | ApiErrorKind::Timeout => Some(self.jittered_delay_ms(attempt, random)),
+            ApiErrorKind::Http { status: 408 | 429 | 502..=504, retry_after_ms, .. } => match retry_after_ms {
+                Some(retry_after_ms) if *retry_after_ms <= self.max_delay_ms => Some(*retry_after_ms),
+                Some(_) => None,
+                None => Some(self.jittered_delay_ms(attempt, random)),
+            },
+            _ => None,
+        }
+    }
+
+    /// `backoff_delay_ms` with the random number in `[0, 1)` that picks the jittered part of the delay.
+    fn jittered_delay_ms(&self, attempt: u32, random: f64) -> u32 {
+        let delay = self
+            .base_delay_ms
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay_ms) as f64;
+        let jitter = self.jitter.clamp(0.0, 1.0);
+
+        (delay * (1.0 - jitter) + delay * jitter * random) as u32
+    }
+
+    fn is_idempotent(method: Method) -> bool {
+        matches!(
+            method,
+            Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::api_client::RequestInfo;
+
+    fn error(kind: ApiErrorKind) -> ApiError {
+        ApiError::new(kind, RequestInfo {
+            method: "GET".to_string(),
+            uri: "/tasks".to_string(),
+        })
+    }
+
+    fn http(status: u16, retry_after_ms: Option<u32>) -> ApiError {
+        error(ApiErrorKind::Http {
+            status,
+            status_text: String::new(),
+            message: None,
+            code: None,
+            retry_after_ms,
+        })
+    }
+
+    fn without_jitter() -> RetryPolicy {
+        RetryPolicy {
+            jitter: 0.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn post_is_not_retried_unless_allowed() {
+        let policy = without_jitter();
+        assert_eq!(policy.retry_delay_ms(&http(503, None), Method::POST, 1, 0.5), None);
+        assert_eq!(policy.retry_delay_ms(&http(503, None), Method::PATCH, 1, 0.5), None);
+        assert_eq!(policy.allow_non_idempotent().retry_delay_ms(&http(503, None), Method::POST, 1, 0.5), Some(500));
+    }
+
+    #[test]
+    fn transient_failures_are_retried() {
+        let policy = without_jitter();
+        for status in [408, 429, 502, 503, 504] {
+            assert_eq!(policy.retry_delay_ms(&http(status, None), Method::GET, 1, 0.5), Some(500), "{}", status);
+        }
+        assert_eq!(policy.retry_delay_ms(&error(ApiErrorKind::Timeout), Method::GET, 1, 0.5), Some(500));
+        let network = error(ApiErrorKind::Network("offline".to_string()));
+        assert_eq!(policy.retry_delay_ms(&network, Method::DELETE, 1, 0.5), Some(500));
+    }
+
+    #[test]
+    fn other_failures_are_not_retried() {
+        let policy = without_jitter();
+        for status in [400, 401, 403, 404, 409, 500, 501] {
+            assert_eq!(policy.retry_delay_ms(&http(status, None), Method::GET, 1, 0.5), None, "{}", status);
+        }
+    }
+
+    #[test]
+    fn retry_after_takes_precedence_over_the_backoff() {
+        let policy = without_jitter();
+        assert_eq!(policy.retry_delay_ms(&http(429, Some(3000)), Method::GET, 1, 0.5), Some(3000));
+        assert_eq!(policy.retry_delay_ms(&http(503, Some(100)), Method::GET, 2, 0.5), Some(100));
+        // waiting longer than the cap is left to the user
+        assert_eq!(policy.retry_delay_ms(&http(429, Some(60_000)), Method::GET, 1, 0.5), None);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = without_jitter();
+        let delays: Vec<u32> = (1..=6).map(|attempt| policy.jittered_delay_ms(attempt, 0.5)).collect();
+        assert_eq!(delays, vec![500, 1000, 2000, 4000, 8000, 8000]);
+        assert_eq!(policy.jittered_delay_ms(40, 0.5), 8000);
+    }
+
+    #[test]
+    fn jitter_randomizes_part_of_the_delay() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.jittered_delay_ms(2, 0.0), 500);
+        assert_eq!(policy.jittered_delay_ms(2, 0.5), 750);
+        assert_eq!(policy.jittered_delay_ms(2, 0.999), 999);
+    }
+
+    #[test]
+    fn retrying_stops_after_max_attempts() {
+        let policy = without_jitter().with_max_attempts(3);
+        assert_eq!(policy.retry_delay_ms(&http(503, None), Method::GET, 2, 0.5), Some(1000));
+        assert_eq!(policy.retry_delay_ms(&http(503, None), Method::GET, 3, 0.5), None);
+        assert_eq!(RetryPolicy::none().retry_delay_ms(&http(503, None), Method::GET, 1, 0.5), None);
+    }
+}
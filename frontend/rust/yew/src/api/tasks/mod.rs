@@ -1,3 +1,7 @@
 pub mod tasks_service;
 pub mod tasks_response;
-pub mod todo_task;
\ No newline at end of file
+pub mod task_events;
+pub mod task_merge;
+pub mod todo_task;
+pub mod recurrence;
+pub mod task_filter;
\ No newline at end of file
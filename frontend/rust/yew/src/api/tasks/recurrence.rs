@@ -0,0 +1,158 @@
+use std::fmt::Display;
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+pub const UNTIL_FORMAT: &str = "%Y-%m-%d";
+pub const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Frequency {
+    Daily,
+    /// On the given days of the week, numbered from Monday as 0.
+    Weekly { weekdays: Vec<u32> },
+    /// On the given day of the month, months without that day use their last one.
+    Monthly { day: u32 },
+    EveryNDays { days: u32 },
+}
+
+/// How a task repeats, the next instance is due on the first matching day after the task was completed.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    /// The last day an instance may be due on, as `YYYY-MM-DD`.
+    #[serde(default)]
+    pub until: Option<String>,
+}
+
+impl RecurrenceRule {
+    pub fn new(frequency: Frequency) -> Self {
+        Self { frequency, until: None }
+    }
+
+    pub fn until(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(self.until.as_ref()?, UNTIL_FORMAT).ok()
+    }
+
+    /// The first occurrence on a day after `completed_at`, at `time`.
+    /// `None` once the rule has ended or can't produce another date.
+    pub fn next_occurrence(&self, completed_at: NaiveDateTime, time: NaiveTime) -> Option<NaiveDateTime> {
+        let next_date = self.next_date(completed_at.date())?;
+        match self.until() {
+            Some(until) if next_date > until => None,
+            _ => Some(next_date.and_time(time)),
+        }
+    }
+
+    fn next_date(&self, after: NaiveDate) -> Option<NaiveDate> {
+        match &self.frequency {
+            Frequency::Daily => after.succ_opt(),
+            Frequency::EveryNDays { days } => match days {
+                0 => None,
+                days => after.checked_add_signed(Duration::days(*days as i64)),
+            },
+            Frequency::Weekly { weekdays } => {
+                // without any weekday it repeats on the weekday it was completed on
+                let matches = |date: &NaiveDate| match weekdays.is_empty() {
+                    true => date.weekday() == after.weekday(),
+                    false => weekdays.contains(&date.weekday().num_days_from_monday()),
+                };
+                after.iter_days().skip(1).take(7).find(matches)
+            }
+            Frequency::Monthly { day } => {
+                if !(1..=31).contains(day) {
+                    return None;
+                }
+                let this_month = day_of_month(after.year(), after.month(), *day)?;
+                if this_month > after {
+                    return Some(this_month);
+                }
+                let (year, month) = match after.month() {
+                    12 => (after.year() + 1, 1),
+                    month => (after.year(), month + 1),
+                };
+                day_of_month(year, month, *day)
+            }
+        }
+    }
+}
+
+/// The given day of the month, or its last day if the month is shorter.
+fn day_of_month(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    (1..=day).rev().find_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+}
+
+impl Display for RecurrenceRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.frequency {
+            Frequency::Daily => write!(f, "Every day")?,
+            Frequency::Weekly { weekdays } if weekdays.is_empty() => write!(f, "Every week")?,
+            Frequency::Weekly { weekdays } => {
+                let names = weekdays
+                    .iter()
+                    .filter_map(|weekday| WEEKDAYS.get(*weekday as usize).copied())
+                    .collect::<Vec<&str>>();
+                write!(f, "Every {}", names.join(", "))?
+            }
+            Frequency::Monthly { day } => write!(f, "Monthly on day {}", day)?,
+            Frequency::EveryNDays { days } => write!(f, "Every {} days", days)?,
+        };
+        match self.until() {
+            Some(until) => write!(f, " until {}", until.format("%d/%m/%y")),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cases shared with the backend tests, so both engines agree on when a task recurs.
+    const FIXTURES: &str = include_str!("../../../../../../integration-tests/fixtures/recurrence.json");
+
+    #[derive(Deserialize)]
+    struct Fixture {
+        name: String,
+        rule: RecurrenceRule,
+        completed_at: String,
+        time: String,
+        next: Option<String>,
+    }
+
+    fn date_time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn next_occurrence_matches_the_shared_fixtures() {
+        let fixtures: Vec<Fixture> = serde_json::from_str(FIXTURES).unwrap();
+        assert!(!fixtures.is_empty());
+        for fixture in fixtures {
+            let time = NaiveTime::parse_from_str(&fixture.time, "%H:%M:%S").unwrap();
+            let next = fixture.rule.next_occurrence(date_time(&fixture.completed_at), time);
+            assert_eq!(next, fixture.next.as_deref().map(date_time), "{}", fixture.name);
+        }
+    }
+
+    #[test]
+    fn malformed_until_is_no_end_date() {
+        let rule = RecurrenceRule {
+            frequency: Frequency::Daily,
+            until: Some("15/03/2023".to_string()),
+        };
+        assert_eq!(rule.until(), None);
+    }
+
+    #[test]
+    fn rules_keep_their_shape_as_json() {
+        let rule = RecurrenceRule {
+            frequency: Frequency::Weekly { weekdays: vec![0, 4] },
+            until: None,
+        };
+        let json = serde_json::to_string(&rule).unwrap();
+        assert_eq!(json, r#"{"frequency":{"kind":"weekly","weekdays":[0,4]},"until":null}"#);
+        assert_eq!(serde_json::from_str::<RecurrenceRule>(&json).unwrap(), rule);
+    }
+}
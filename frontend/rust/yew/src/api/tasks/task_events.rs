@@ -0,0 +1,243 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use gloo::{console::log, events::EventListener, timers::callback::Timeout};
+use serde::Deserialize;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{EventSource, MessageEvent};
+use yew::Callback;
+
+use crate::{
+    api::{api_config::ApiConfig, request_options::RetryPolicy},
+    utils::ErrorAction,
+};
+
+use super::{tasks_service::TasksService, todo_task::TodoTask};
+
+const TASK_EVENTS_URI: &str = "/tasks/events";
+const RECONNECT_BASE_DELAY_MS: u32 = 1000;
+const RECONNECT_MAX_DELAY_MS: u32 = 60000;
+
+/// A change made to the user's tasks on the server, possibly from another device.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskEvent {
+    Created(TodoTask),
+    Updated(TodoTask),
+    Deleted(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionState {
+    Connecting,
+    Open,
+    Reconnecting { delay_ms: u32 },
+}
+
+#[derive(Deserialize)]
+struct DeletedTask {
+    id: i32,
+}
+
+/// Subscribes to the server-sent task event stream. The stream sends `created` and `updated`
+/// events with the task as data and `deleted` events with `{"id": ..}`.
+/// EventSource can't send the token, so every connection is opened with a single use ticket instead.
+/// Reconnects with exponential backoff whenever the connection drops, and closes it when dropped.
+pub struct TaskEventsClient {
+    inner: Rc<RefCell<Inner>>,
+}
+
+struct Inner {
+    token: String,
+    on_event: Callback<TaskEvent>,
+    on_state: Callback<ConnectionState>,
+    backoff: RetryPolicy,
+    attempt: u32,
+    source: Option<EventSource>,
+    listeners: Vec<EventListener>,
+    reconnect: Option<Timeout>,
+}
+
+impl TaskEventsClient {
+    pub fn connect(token: &str, on_event: Callback<TaskEvent>, on_state: Callback<ConnectionState>) -> Self {
+        let backoff = RetryPolicy {
+            base_delay_ms: RECONNECT_BASE_DELAY_MS,
+            max_delay_ms: RECONNECT_MAX_DELAY_MS,
+            ..Default::default()
+        };
+        let inner = Rc::new(RefCell::new(Inner {
+            token: token.to_string(),
+            on_event,
+            on_state,
+            backoff,
+            attempt: 0,
+            source: None,
+            listeners: Vec::new(),
+            reconnect: None,
+        }));
+
+        open(&inner);
+        Self { inner }
+    }
+}
+
+impl Drop for TaskEventsClient {
+    fn drop(&mut self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.reconnect = None;
+        inner.listeners.clear();
+        if let Some(source) = inner.source.take() {
+            source.close();
+        }
+    }
+}
+
+fn open(inner: &Rc<RefCell<Inner>>) {
+    let (token, on_state) = {
+        let inner = inner.borrow();
+        (inner.token.clone(), inner.on_state.clone())
+    };
+    on_state.emit(ConnectionState::Connecting);
+
+    let weak = Rc::downgrade(inner);
+    spawn_local(async move {
+        let ticket = TasksService::get_events_ticket(token).await;
+        let inner = match weak.upgrade() {
+            Some(inner) => inner,
+            None => return,
+        };
+        match ticket {
+            Ok(ticket) => open_source(&inner, &ticket),
+            // the session is over, a new login connects again
+            Err(error) if ErrorAction::from(&error) == ErrorAction::EndSession => {
+                log!(format!("Not allowed to open the task event stream: {:?}", error))
+            }
+            Err(error) => {
+                log!(format!("Unable to get a task event stream ticket: {:?}", error));
+                schedule_reconnect(&inner);
+            }
+        }
+    });
+}
+
+fn open_source(inner: &Rc<RefCell<Inner>>, ticket: &str) {
+    let url = format!(
+        "{}{}?ticket={}",
+        ApiConfig::get().api_uri,
+        TASK_EVENTS_URI,
+        String::from(js_sys::encode_uri_component(ticket))
+    );
+
+    let source = match EventSource::new(&url) {
+        Ok(source) => source,
+        Err(error) => {
+            log!("Unable to open the task event stream:", error);
+            return schedule_reconnect(inner);
+        }
+    };
+
+    let weak = Rc::downgrade(inner);
+    let mut listeners = vec![
+        EventListener::new(&source, "open", {
+            let weak = weak.clone();
+            move |_| {
+                if let Some(inner) = weak.upgrade() {
+                    let on_state = {
+                        let mut inner = inner.borrow_mut();
+                        inner.attempt = 0;
+                        inner.on_state.clone()
+                    };
+                    on_state.emit(ConnectionState::Open);
+                }
+            }
+        }),
+        // the browser's own reconnect doesn't back off and gives up on some errors, so we handle it ourselves
+        EventListener::new(&source, "error", {
+            let weak = weak.clone();
+            move |_| {
+                if let Some(inner) = weak.upgrade() {
+                    if let Some(source) = inner.borrow_mut().source.take() {
+                        source.close();
+                    }
+                    schedule_reconnect(&inner);
+                }
+            }
+        }),
+    ];
+
+    for name in ["created", "updated", "deleted"] {
+        let weak = weak.clone();
+        listeners.push(EventListener::new(&source, name, move |event| {
+            let data = event
+                .dyn_ref::<MessageEvent>()
+                .and_then(|event| event.data().as_string())
+                .unwrap_or_default();
+            let event = match parse_event(name, &data) {
+                Some(event) => event,
+                None => return log!(format!("Ignoring malformed task event {}: {}", name, data)),
+            };
+            if let Some(inner) = weak.upgrade() {
+                let on_event = inner.borrow().on_event.clone();
+                on_event.emit(event);
+            }
+        }));
+    }
+
+    let mut inner = inner.borrow_mut();
+    inner.source = Some(source);
+    inner.listeners = listeners;
+}
+
+fn schedule_reconnect(inner: &Rc<RefCell<Inner>>) {
+    let (delay_ms, on_state) = {
+        let mut inner = inner.borrow_mut();
+        inner.attempt += 1;
+        (inner.backoff.backoff_delay_ms(inner.attempt), inner.on_state.clone())
+    };
+    on_state.emit(ConnectionState::Reconnecting { delay_ms });
+
+    let weak: Weak<RefCell<Inner>> = Rc::downgrade(inner);
+    let timeout = Timeout::new(delay_ms, move || {
+        if let Some(inner) = weak.upgrade() {
+            open(&inner);
+        }
+    });
+    inner.borrow_mut().reconnect = Some(timeout);
+}
+
+fn parse_event(name: &str, data: &str) -> Option<TaskEvent> {
+    match name {
+        "created" => serde_json::from_str(data).ok().map(TaskEvent::Created),
+        "updated" => serde_json::from_str(data).ok().map(TaskEvent::Updated),
+        "deleted" => serde_json::from_str::<DeletedTask>(data).ok().map(|task| TaskEvent::Deleted(task.id)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TASK: &str = r#"{"id":3,"title":"Water the plants","priority":"B","description":null,"created_at":null,"completed_at":null,"user_id":1}"#;
+
+    #[test]
+    fn created_and_updated_carry_the_task() {
+        let task = serde_json::from_str::<TodoTask>(TASK).unwrap();
+        assert_eq!(parse_event("created", TASK), Some(TaskEvent::Created(task.clone())));
+        assert_eq!(parse_event("updated", TASK), Some(TaskEvent::Updated(task)));
+    }
+
+    #[test]
+    fn deleted_carries_the_id() {
+        assert_eq!(parse_event("deleted", r#"{"id":3}"#), Some(TaskEvent::Deleted(3)));
+    }
+
+    #[test]
+    fn malformed_and_unknown_events_are_ignored() {
+        assert_eq!(parse_event("created", r#"{"id":3}"#), None);
+        assert_eq!(parse_event("deleted", "3"), None);
+        assert_eq!(parse_event("renamed", TASK), None);
+    }
+}
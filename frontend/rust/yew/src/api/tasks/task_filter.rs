@@ -0,0 +1,371 @@
+use chrono::{DateTime, Local, NaiveDate, Utc};
+
+use super::todo_task::{Priority, TodoTask};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateField {
+    Due,
+    Created,
+    Completed,
+}
+
+impl DateField {
+    pub const ALL: [DateField; 3] = [DateField::Due, DateField::Created, DateField::Completed];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateField::Due => "Due",
+            DateField::Created => "Created",
+            DateField::Completed => "Completed",
+        }
+    }
+
+    pub fn value(&self, task: &TodoTask) -> Option<DateTime<Utc>> {
+        match self {
+            DateField::Due => task.due_at(),
+            DateField::Created => task.created_at(),
+            DateField::Completed => task.completed_at(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterCondition {
+    Status { completed: bool },
+    /// Any of the priorities.
+    Priorities(Vec<Priority>),
+    /// Found in the title or the description, ignoring case.
+    Text(String),
+    /// The task has the date and it's on one of the days between the bounds, as the user sees them.
+    /// A missing bound leaves that side open.
+    DateRange {
+        field: DateField,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    },
+    /// Any of the tags.
+    Tags(Vec<String>),
+}
+
+impl FilterCondition {
+    pub fn matches(&self, task: &TodoTask) -> bool {
+        match self {
+            FilterCondition::Status { completed } => task.completed() == *completed,
+            FilterCondition::Priorities(priorities) => match &task.priority {
+                Some(priority) => priorities.contains(priority),
+                None => false,
+            },
+            FilterCondition::Text(text) => {
+                let text = text.to_lowercase();
+                task.title.to_lowercase().contains(&text)
+                    || task.description.as_ref().map_or(false, |description| description.to_lowercase().contains(&text))
+            }
+            FilterCondition::DateRange { field, from, to } => match field.value(task) {
+                Some(date) => {
+                    let date = date.with_timezone(&Local).naive_local().date();
+                    from.map_or(true, |from| date >= from) && to.map_or(true, |to| date <= to)
+                }
+                None => false,
+            },
+            FilterCondition::Tags(tags) => tags.iter().any(|tag| task.has_tag(tag)),
+        }
+    }
+
+    /// A filter holds a single condition of each kind, and a single date range of each field.
+    pub fn same_kind(&self, other: &FilterCondition) -> bool {
+        match (self, other) {
+            (FilterCondition::DateRange { field, .. }, FilterCondition::DateRange { field: other_field, .. }) => field == other_field,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+
+    /// The condition as it's shown on its chip.
+    pub fn label(&self) -> String {
+        match self {
+            FilterCondition::Status { completed: true } => "Completed".to_string(),
+            FilterCondition::Status { completed: false } => "Incomplete".to_string(),
+            FilterCondition::Priorities(priorities) => format!(
+                "Priority {}",
+                priorities.iter().map(|priority| priority.to_string()).collect::<Vec<String>>().join(" or ")
+            ),
+            FilterCondition::Text(text) => format!("\"{}\"", text),
+            FilterCondition::DateRange { field, from, to } => {
+                let format = |date: &NaiveDate| date.format("%d/%m/%y").to_string();
+                match (from, to) {
+                    (Some(from), Some(to)) => format!("{} {} - {}", field.label(), format(from), format(to)),
+                    (Some(from), None) => format!("{} from {}", field.label(), format(from)),
+                    (None, Some(to)) => format!("{} until {}", field.label(), format(to)),
+                    (None, None) => format!("{} at any time", field.label()),
+                }
+            }
+            FilterCondition::Tags(tags) => format!("Tagged {}", tags.join(" or ")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Combinator {
+    /// Every condition has to match.
+    And,
+    /// Any condition has to match.
+    Or,
+}
+
+/// Conditions a task has to match to be listed, a filter without any lists every task.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskFilter {
+    pub conditions: Vec<FilterCondition>,
+    pub combinator: Combinator,
+}
+
+impl Default for TaskFilter {
+    fn default() -> Self {
+        Self {
+            conditions: Vec::new(),
+            combinator: Combinator::And,
+        }
+    }
+}
+
+impl TaskFilter {
+    pub fn matches(&self, task: &TodoTask) -> bool {
+        if self.conditions.is_empty() {
+            return true;
+        }
+        match self.combinator {
+            Combinator::And => self.conditions.iter().all(|condition| condition.matches(task)),
+            Combinator::Or => self.conditions.iter().any(|condition| condition.matches(task)),
+        }
+    }
+
+    pub fn apply(&self, tasks: Vec<TodoTask>) -> Vec<TodoTask> {
+        tasks.into_iter().filter(|task| self.matches(task)).collect()
+    }
+
+    /// Adds the condition in place of the one of the same kind.
+    pub fn set(&mut self, condition: FilterCondition) {
+        match self.conditions.iter().position(|existing| existing.same_kind(&condition)) {
+            Some(index) => self.conditions[index] = condition,
+            None => self.conditions.push(condition),
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.conditions.len() {
+            self.conditions.remove(index);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.conditions.clear();
+    }
+
+    pub fn status(&self) -> Option<bool> {
+        self.conditions.iter().find_map(|condition| match condition {
+            FilterCondition::Status { completed } => Some(*completed),
+            _ => None,
+        })
+    }
+
+    pub fn set_status(&mut self, completed: Option<bool>) {
+        match completed {
+            Some(completed) => self.set(FilterCondition::Status { completed }),
+            None => self.conditions.retain(|condition| !matches!(condition, FilterCondition::Status { .. })),
+        }
+    }
+
+    pub fn priorities(&self) -> Vec<Priority> {
+        self.conditions
+            .iter()
+            .find_map(|condition| match condition {
+                FilterCondition::Priorities(priorities) => Some(priorities.clone()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Adds the priority to the priorities a task may have, or takes it out if it's there already.
+    pub fn toggle_priority(&mut self, priority: Priority) {
+        let mut priorities = self.priorities();
+        match priorities.iter().position(|selected| *selected == priority) {
+            Some(index) => {
+                priorities.remove(index);
+            }
+            None => {
+                priorities.push(priority);
+                priorities.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            }
+        }
+        match priorities.is_empty() {
+            true => self.conditions.retain(|condition| !matches!(condition, FilterCondition::Priorities(_))),
+            false => self.set(FilterCondition::Priorities(priorities)),
+        }
+    }
+
+    pub fn text(&self) -> Option<String> {
+        self.conditions.iter().find_map(|condition| match condition {
+            FilterCondition::Text(text) => Some(text.clone()),
+            _ => None,
+        })
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        let text = text.trim();
+        match text.is_empty() {
+            true => self.conditions.retain(|condition| !matches!(condition, FilterCondition::Text(_))),
+            false => self.set(FilterCondition::Text(text.to_string())),
+        }
+    }
+
+    pub fn date_range(&self, field: DateField) -> Option<(Option<NaiveDate>, Option<NaiveDate>)> {
+        self.conditions.iter().find_map(|condition| match condition {
+            FilterCondition::DateRange { field: range_field, from, to } if *range_field == field => Some((*from, *to)),
+            _ => None,
+        })
+    }
+
+    pub fn tags(&self) -> Vec<String> {
+        self.conditions
+            .iter()
+            .find_map(|condition| match condition {
+                FilterCondition::Tags(tags) => Some(tags.clone()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags().iter().any(|selected| selected.eq_ignore_ascii_case(tag))
+    }
+
+    /// Adds the tag to the tags a task may have, or takes it out if it's there already.
+    pub fn toggle_tag(&mut self, tag: &str) {
+        let mut tags = self.tags();
+        match tags.iter().position(|selected| selected.eq_ignore_ascii_case(tag)) {
+            Some(index) => {
+                tags.remove(index);
+            }
+            None => tags.push(tag.to_string()),
+        }
+        match tags.is_empty() {
+            true => self.conditions.retain(|condition| !matches!(condition, FilterCondition::Tags(_))),
+            false => self.set(FilterCondition::Tags(tags)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveTime, TimeZone};
+
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn task(title: &str, priority: Option<Priority>) -> TodoTask {
+        TodoTask {
+            title: title.to_string(),
+            priority,
+            ..Default::default()
+        }
+    }
+
+    /// A task due on the given day at the given time, as the user sees it.
+    fn due_on(day: NaiveDate, time: NaiveTime) -> TodoTask {
+        let mut task = task("Due", None);
+        let due_at = Local.from_local_datetime(&day.and_time(time)).unwrap();
+        task.set_due_at(Some(due_at.with_timezone(&Utc)));
+        task
+    }
+
+    fn filter(combinator: Combinator, conditions: Vec<FilterCondition>) -> TaskFilter {
+        TaskFilter { conditions, combinator }
+    }
+
+    #[test]
+    fn and_needs_every_condition() {
+        let filter = filter(
+            Combinator::And,
+            vec![FilterCondition::Text("report".to_string()), FilterCondition::Priorities(vec![Priority::A])],
+        );
+
+        assert!(filter.matches(&task("Write the report", Some(Priority::A))));
+        assert!(!filter.matches(&task("Write the report", Some(Priority::B))));
+        assert!(!filter.matches(&task("Water the plants", Some(Priority::A))));
+    }
+
+    #[test]
+    fn or_needs_any_condition() {
+        let filter = filter(
+            Combinator::Or,
+            vec![FilterCondition::Text("report".to_string()), FilterCondition::Priorities(vec![Priority::A])],
+        );
+
+        assert!(filter.matches(&task("Write the report", Some(Priority::B))));
+        assert!(filter.matches(&task("Water the plants", Some(Priority::A))));
+        assert!(!filter.matches(&task("Water the plants", None)));
+    }
+
+    #[test]
+    fn an_empty_filter_matches_every_task() {
+        for combinator in [Combinator::And, Combinator::Or] {
+            assert!(filter(combinator, Vec::new()).matches(&task("Anything", None)));
+        }
+    }
+
+    #[test]
+    fn set_replaces_the_condition_of_the_same_kind() {
+        let mut filter = TaskFilter::default();
+        filter.set(FilterCondition::Text("report".to_string()));
+        filter.set(FilterCondition::Status { completed: true });
+        filter.set(FilterCondition::Text("plants".to_string()));
+
+        assert_eq!(
+            filter.conditions,
+            vec![FilterCondition::Text("plants".to_string()), FilterCondition::Status { completed: true }]
+        );
+    }
+
+    #[test]
+    fn date_ranges_of_different_fields_are_kept_apart() {
+        let range = |field, day| FilterCondition::DateRange { field, from: Some(day), to: None };
+        let mut filter = TaskFilter::default();
+        filter.set(range(DateField::Due, date(2022, 10, 1)));
+        filter.set(range(DateField::Created, date(2022, 10, 2)));
+        filter.set(range(DateField::Due, date(2022, 10, 3)));
+
+        assert!(range(DateField::Due, date(2022, 1, 1)).same_kind(&range(DateField::Due, date(2022, 2, 2))));
+        assert!(!range(DateField::Due, date(2022, 1, 1)).same_kind(&range(DateField::Created, date(2022, 1, 1))));
+        assert_eq!(filter.date_range(DateField::Due), Some((Some(date(2022, 10, 3)), None)));
+        assert_eq!(filter.date_range(DateField::Created), Some((Some(date(2022, 10, 2)), None)));
+        assert_eq!(filter.conditions.len(), 2);
+    }
+
+    #[test]
+    fn date_range_bounds_include_the_whole_day() {
+        let range = FilterCondition::DateRange {
+            field: DateField::Due,
+            from: Some(date(2022, 10, 10)),
+            to: Some(date(2022, 10, 12)),
+        };
+        let start_of_day = NaiveTime::from_hms_opt(0, 0, 0).unwrap();
+        let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
+
+        assert!(range.matches(&due_on(date(2022, 10, 10), start_of_day)));
+        assert!(range.matches(&due_on(date(2022, 10, 12), end_of_day)));
+        assert!(!range.matches(&due_on(date(2022, 10, 9), end_of_day)));
+        assert!(!range.matches(&due_on(date(2022, 10, 13), start_of_day)));
+        assert!(!range.matches(&task("Never due", None)));
+    }
+
+    #[test]
+    fn open_date_range_bounds_match_any_day() {
+        let range = FilterCondition::DateRange { field: DateField::Due, from: None, to: Some(date(2022, 10, 12)) };
+        let noon = NaiveTime::from_hms_opt(12, 0, 0).unwrap();
+
+        assert!(range.matches(&due_on(date(2000, 1, 1), noon)));
+        assert!(!range.matches(&due_on(date(2022, 10, 13), noon)));
+    }
+}
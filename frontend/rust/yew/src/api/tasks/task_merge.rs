@@ -0,0 +1,254 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use super::todo_task::TodoTask;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum TaskField {
+    Title,
+    Priority,
+    Description,
+    Completed,
+    DueDate,
+    Tags,
+    Subtasks,
+    Recurrence,
+}
+
+impl TaskField {
+    pub const ALL: [TaskField; 8] = [
+        TaskField::Title,
+        TaskField::Priority,
+        TaskField::Description,
+        TaskField::Completed,
+        TaskField::DueDate,
+        TaskField::Tags,
+        TaskField::Subtasks,
+        TaskField::Recurrence,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TaskField::Title => "Title",
+            TaskField::Priority => "Priority",
+            TaskField::Description => "Description",
+            TaskField::Completed => "Completed",
+            TaskField::DueDate => "Due date",
+            TaskField::Tags => "Tags",
+            TaskField::Subtasks => "Subtasks",
+            TaskField::Recurrence => "Repeats",
+        }
+    }
+
+    /// The field as it's shown to the user.
+    pub fn display(&self, task: &TodoTask) -> String {
+        match self {
+            TaskField::Title => task.title.clone(),
+            TaskField::Priority => match &task.priority {
+                Some(priority) => priority.to_string(),
+                None => "-".to_string(),
+            },
+            TaskField::Description => task.description.clone().unwrap_or_default(),
+            TaskField::Completed => match task.completed() {
+                true => "Yes".to_string(),
+                false => "No".to_string(),
+            },
+            TaskField::DueDate => match task.due_at() {
+                Some(due_at) => due_at.with_timezone(&Local).format("%H:%M %d/%m/%y").to_string(),
+                None => "-".to_string(),
+            },
+            TaskField::Tags => task.tags.join(", "),
+            TaskField::Subtasks => task
+                .subtasks
+                .iter()
+                .map(|subtask| format!("[{}] {}", if subtask.completed { "x" } else { " " }, subtask.title))
+                .collect::<Vec<String>>()
+                .join(", "),
+            TaskField::Recurrence => match &task.recurrence {
+                Some(recurrence) => recurrence.to_string(),
+                None => "-".to_string(),
+            },
+        }
+    }
+
+    pub fn differs(&self, a: &TodoTask, b: &TodoTask) -> bool {
+        match self {
+            TaskField::Title => a.title != b.title,
+            TaskField::Priority => a.priority != b.priority,
+            TaskField::Description => a.description != b.description,
+            // only whether it's completed matters, not when exactly
+            TaskField::Completed => a.completed() != b.completed(),
+            TaskField::DueDate => a.due_at() != b.due_at(),
+            TaskField::Tags => a.tags != b.tags,
+            TaskField::Subtasks => a.subtasks != b.subtasks,
+            TaskField::Recurrence => a.recurrence != b.recurrence,
+        }
+    }
+
+    pub fn copy(&self, from: &TodoTask, to: &mut TodoTask) {
+        match self {
+            TaskField::Title => to.title = from.title.clone(),
+            TaskField::Priority => to.priority = from.priority.clone(),
+            TaskField::Description => to.description = from.description.clone(),
+            TaskField::Completed => to.completed_at = from.completed_at.clone(),
+            TaskField::DueDate => to.due_at = from.due_at.clone(),
+            TaskField::Tags => to.tags = from.tags.clone(),
+            TaskField::Subtasks => to.subtasks = from.subtasks.clone(),
+            TaskField::Recurrence => to.recurrence = from.recurrence.clone(),
+        }
+    }
+}
+
+/// Three-way merge of a task edited locally while it also changed on the server.
+/// `base` is the task as it was when editing started.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TaskMerge {
+    pub base: TodoTask,
+    pub local: TodoTask,
+    pub remote: TodoTask,
+    /// The server version with the local changes applied, conflicting fields keep the local value until resolved.
+    pub merged: TodoTask,
+    pub conflicts: Vec<TaskField>,
+}
+
+impl TaskMerge {
+    pub fn new(base: TodoTask, local: TodoTask, remote: TodoTask) -> Self {
+        let mut merged = remote.clone();
+        let mut conflicts = Vec::new();
+
+        for field in TaskField::ALL {
+            let changed_locally = field.differs(&local, &base);
+            if changed_locally {
+                field.copy(&local, &mut merged);
+            }
+            if changed_locally && field.differs(&remote, &base) && field.differs(&local, &remote) {
+                conflicts.push(field);
+            }
+        }
+
+        Self {
+            base,
+            local,
+            remote,
+            merged,
+            conflicts,
+        }
+    }
+
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    /// Picks the local or the remote value of a conflicting field.
+    pub fn resolve(&mut self, field: TaskField, keep_local: bool) {
+        match keep_local {
+            true => field.copy(&self.local, &mut self.merged),
+            false => field.copy(&self.remote, &mut self.merged),
+        }
+    }
+
+    pub fn keeps_local(&self, field: TaskField) -> bool {
+        !field.differs(&self.merged, &self.local)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::tasks::todo_task::{Priority, Subtask};
+
+    fn base() -> TodoTask {
+        TodoTask {
+            id: 7,
+            title: "Release v2".to_string(),
+            priority: Some(Priority::B),
+            description: Some("Ship it".to_string()),
+            tags: vec!["work".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unchanged_task_merges_to_the_remote_version() {
+        let merge = TaskMerge::new(base(), base(), base());
+        assert!(!merge.has_conflicts());
+        assert_eq!(merge.merged, base());
+    }
+
+    #[test]
+    fn only_local_change_is_applied() {
+        let local = TodoTask { title: "Release v2.0".to_string(), ..base() };
+        let merge = TaskMerge::new(base(), local.clone(), base());
+        assert!(!merge.has_conflicts());
+        assert_eq!(merge.merged, local);
+    }
+
+    #[test]
+    fn only_remote_change_is_kept() {
+        let remote = TodoTask { priority: Some(Priority::A), ..base() };
+        let merge = TaskMerge::new(base(), base(), remote.clone());
+        assert!(!merge.has_conflicts());
+        assert_eq!(merge.merged, remote);
+    }
+
+    #[test]
+    fn changes_to_different_fields_are_combined() {
+        let local = TodoTask { title: "Release v2.0".to_string(), ..base() };
+        let remote = TodoTask { tags: vec!["work".to_string(), "urgent".to_string()], ..base() };
+        let merge = TaskMerge::new(base(), local, remote.clone());
+        assert!(!merge.has_conflicts());
+        assert_eq!(merge.merged.title, "Release v2.0");
+        assert_eq!(merge.merged.tags, remote.tags);
+    }
+
+    #[test]
+    fn same_change_on_both_sides_is_no_conflict() {
+        let edited = TodoTask { description: Some("Ship it today".to_string()), ..base() };
+        let merge = TaskMerge::new(base(), edited.clone(), edited.clone());
+        assert!(!merge.has_conflicts());
+        assert_eq!(merge.merged, edited);
+    }
+
+    #[test]
+    fn different_changes_to_the_same_field_conflict() {
+        let local = TodoTask { title: "Release v2.0".to_string(), ..base() };
+        let remote = TodoTask { title: "Release 2".to_string(), priority: Some(Priority::A), ..base() };
+        let merge = TaskMerge::new(base(), local, remote);
+        assert_eq!(merge.conflicts, vec![TaskField::Title]);
+        // the conflicting field keeps the local value until it's resolved
+        assert_eq!(merge.merged.title, "Release v2.0");
+        assert!(merge.keeps_local(TaskField::Title));
+        assert_eq!(merge.merged.priority, Some(Priority::A));
+    }
+
+    #[test]
+    fn completing_on_both_sides_at_different_times_is_no_conflict() {
+        let local = TodoTask { completed_at: Some("2023-03-14 09:00:00.000".to_string()), ..base() };
+        let remote = TodoTask { completed_at: Some("2023-03-14 10:00:00.000".to_string()), ..base() };
+        let merge = TaskMerge::new(base(), local, remote);
+        assert!(!merge.has_conflicts());
+        assert!(merge.merged.completed());
+    }
+
+    #[test]
+    fn resolving_picks_the_remote_value() {
+        let local = TodoTask { title: "Release v2.0".to_string(), ..base() };
+        let remote = TodoTask { title: "Release 2".to_string(), ..base() };
+        let mut merge = TaskMerge::new(base(), local, remote);
+        merge.resolve(TaskField::Title, false);
+        assert_eq!(merge.merged.title, "Release 2");
+        assert!(!merge.keeps_local(TaskField::Title));
+    }
+
+    #[test]
+    fn resolving_picks_the_local_value_again() {
+        let local = TodoTask { subtasks: vec![Subtask { title: "Tag".to_string(), completed: true }], ..base() };
+        let remote = TodoTask { subtasks: vec![Subtask { title: "Tag".to_string(), completed: false }], ..base() };
+        let mut merge = TaskMerge::new(base(), local.clone(), remote);
+        assert_eq!(merge.conflicts, vec![TaskField::Subtasks]);
+        merge.resolve(TaskField::Subtasks, false);
+        merge.resolve(TaskField::Subtasks, true);
+        assert_eq!(merge.merged.subtasks, local.subtasks);
+        assert!(merge.keeps_local(TaskField::Subtasks));
+    }
+}
@@ -11,3 +11,13 @@ pub struct TasksResponse {
 pub struct TaskResponse {
     pub data: TodoTask
 }
+
+#[derive(Serialize, Deserialize)]
+pub struct EventsTicket {
+    pub ticket: String
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EventsTicketResponse {
+    pub data: EventsTicket
+}
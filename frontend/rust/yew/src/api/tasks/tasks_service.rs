@@ -1,8 +1,10 @@
+use chrono::Local;
 use reqwasm::http::{Headers, Method};
+use web_sys::AbortSignal;
 
-use crate::api::{api_client::{ApiClient, ApiError}, api_error_response::ApiErrorResponse};
+use crate::api::{api_client::{ApiClient, ApiError, Conditional}, api_error_response::ApiErrorResponse, request_options::RequestOptions};
 
-use super::{todo_task::TodoTask, tasks_response::{TasksResponse, TaskResponse}};
+use super::{todo_task::TodoTask, tasks_response::{EventsTicketResponse, TasksResponse, TaskResponse}};
 
 pub struct TasksService;
 
@@ -15,6 +17,7 @@ impl TasksService {
             Method::POST,
             Some(serde_json::to_string(&task).unwrap()),
             Some(TasksService::get_headers(token)),
+            RequestOptions::default(),
         )
         .await;
 
@@ -24,33 +27,37 @@ impl TasksService {
         };
     }
 
-    pub async fn update_task(token: String, task: TodoTask) -> Result<(), ApiError> {
+    pub async fn update_task(token: String, task: TodoTask) -> Result<TodoTask, ApiError> {
         let response = ApiClient::send_json::<TaskResponse, ApiErrorResponse>(
             format!("{}/{}", TASKS_URI, &task.id).as_str(),
             Method::PATCH,
             Some(serde_json::to_string(&task).unwrap()),
             Some(TasksService::get_headers(token)),
+            RequestOptions::default(),
         )
         .await;
 
         return match response {
-            Ok(_) => Ok(()),
+            Ok(ok) => Ok(ok.data),
             Err(error) => Err(error),
         };
     }
 
-    pub async fn task_toggle_completed(token: String, id: i32) -> Result<(), ApiError> {
+    /// Completing a recurring task also creates its next instance, which the rule moves on to.
+    /// The server does both at once, due on the next day as the user's time zone sees it.
+    pub async fn task_toggle_completed(token: String, id: i32) -> Result<TodoTask, ApiError> {
         let body: Option<&str> = None;
         let response = ApiClient::send_json::<TaskResponse, ApiErrorResponse>(
-            format!("{}/{}/toggle-completed", TASKS_URI, &id).as_str(),
+            format!("{}/{}/toggle-completed?utcOffsetMinutes={}", TASKS_URI, &id, Local::now().offset().local_minus_utc() / 60).as_str(),
             Method::PATCH,
             body,
             Some(TasksService::get_headers(token)),
+            RequestOptions::default(),
         )
         .await;
 
         return match response {
-            Ok(_) => Ok(()),
+            Ok(ok) => Ok(ok.data),
             Err(error) => Err(error),
         };
     }
@@ -62,6 +69,7 @@ impl TasksService {
             Method::DELETE,
             body,
             Some(TasksService::get_headers(token)),
+            RequestOptions::default(),
         )
         .await;
 
@@ -71,13 +79,34 @@ impl TasksService {
         };
     }
 
-    pub async fn get_tasks(token: String) -> Result<Vec<TodoTask>, ApiError> {
+    /// Fetches the tasks unless they are unchanged since the response `etag` came from.
+    pub async fn get_tasks(token: String, etag: Option<String>, abort_signal: Option<AbortSignal>) -> Result<Conditional<Vec<TodoTask>>, ApiError> {
         let body: Option<&str> = None;
-        let response = ApiClient::send_json::<TasksResponse, ApiErrorResponse>(
+        let response = ApiClient::send_json_conditional::<TasksResponse, ApiErrorResponse>(
             TASKS_URI,
             Method::GET,
             body,
+            TasksService::get_headers(token),
+            etag.as_deref(),
+            RequestOptions::default().with_abort_signal(abort_signal),
+        )
+        .await;
+
+        return match response {
+            Ok(Conditional::Modified { data, etag }) => Ok(Conditional::Modified { data: data.data, etag }),
+            Ok(Conditional::NotModified) => Ok(Conditional::NotModified),
+            Err(error) => Err(error)
+        };
+    }
+
+    pub async fn get_task(token: String, id: i32, abort_signal: Option<AbortSignal>) -> Result<TodoTask, ApiError> {
+        let body: Option<&str> = None;
+        let response = ApiClient::send_json::<TaskResponse, ApiErrorResponse>(
+            format!("{}/{}", TASKS_URI, id).as_str(),
+            Method::GET,
+            body,
             Some(TasksService::get_headers(token)),
+            RequestOptions::default().with_abort_signal(abort_signal),
         )
         .await;
 
@@ -87,6 +116,24 @@ impl TasksService {
         };
     }
 
+    /// A single use ticket that opens the task event stream, which can't be sent the token.
+    pub async fn get_events_ticket(token: String) -> Result<String, ApiError> {
+        let body: Option<&str> = None;
+        let response = ApiClient::send_json::<EventsTicketResponse, ApiErrorResponse>(
+            format!("{}/events/ticket", TASKS_URI).as_str(),
+            Method::POST,
+            body,
+            Some(TasksService::get_headers(token)),
+            RequestOptions::default(),
+        )
+        .await;
+
+        return match response {
+            Ok(ok) => Ok(ok.data.ticket),
+            Err(error) => Err(error)
+        };
+    }
+
     fn get_headers(token: String) -> Headers {
         let headers = Headers::default();
         headers.append("content-type", "application/json");
@@ -1,7 +1,9 @@
-use chrono::{Utc, DateTime, TimeZone};
-use serde::{Deserialize, Serialize};
+use chrono::{Duration, Utc, DateTime, TimeZone, Local};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::{cell::RefCell, fmt::Display, str::FromStr};
 
+use super::recurrence::RecurrenceRule;
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, PartialOrd)]
 pub enum Priority {
     A,
@@ -36,16 +38,70 @@ pub struct TodoTask {
     pub description: Option<String>,
     pub created_at: Option<String>,
     pub completed_at: Option<String>,
+    #[serde(default)]
+    pub due_at: Option<String>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub tags: Vec<String>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub subtasks: Vec<Subtask>,
+    #[serde(default)]
+    pub recurrence: Option<RecurrenceRule>,
     pub user_id: i32
 }
 
+/// A checklist item of a task, kept in the order the user put them.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct Subtask {
+    pub title: String,
+    pub completed: bool,
+}
+
+/// The backend sends `null` for columns that were never set.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
 const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S.%f";
+/// Tasks due within this many hours are highlighted.
+pub const DUE_SOON_HOURS: i64 = 24;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DueState {
+    /// Completed or without a due date.
+    None,
+    Upcoming,
+    DueSoon,
+    Overdue,
+}
 
 impl TodoTask {
     pub fn completed(&self) -> bool {
         self.completed_at.is_some()
     }
 
+    pub fn toggle_completed(&mut self) {
+        self.completed_at = match self.completed_at {
+            Some(_) => None,
+            None => Some(Utc::now().format(DATE_FORMAT).to_string()),
+        };
+    }
+
+    /// Fills in what the server would set on creation, for tasks created while offline.
+    pub fn create_locally(&mut self, id: i32, user_id: i32, as_completed: bool) {
+        let now = Utc::now().format(DATE_FORMAT).to_string();
+        self.id = id;
+        self.user_id = user_id;
+        self.created_at = Some(now.clone());
+        self.completed_at = match as_completed {
+            true => Some(now),
+            false => None,
+        };
+    }
+
     pub fn created_at(&self) -> Option<DateTime<Utc>> {
         if let None = self.created_at {
             return None;
@@ -65,6 +121,84 @@ impl TodoTask {
             Err(_) => None
         };
     }
+
+    pub fn due_at(&self) -> Option<DateTime<Utc>> {
+        if let None = self.due_at {
+            return None;
+        }
+        return match Utc.datetime_from_str(&self.due_at.clone().unwrap().trim(), DATE_FORMAT) {
+            Ok(date) => Some(date),
+            Err(_) => None
+        };
+    }
+
+    pub fn set_due_at(&mut self, due_at: Option<DateTime<Utc>>) {
+        self.due_at = due_at.map(|due_at| due_at.format(DATE_FORMAT).to_string());
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|existing| existing.eq_ignore_ascii_case(tag))
+    }
+
+    /// Adds a trimmed tag, tags differing only in case are the same tag.
+    pub fn add_tag(&mut self, tag: &str) {
+        let tag = tag.trim();
+        if !tag.is_empty() && !self.has_tag(tag) {
+            self.tags.push(tag.to_string());
+        }
+    }
+
+    pub fn remove_tag(&mut self, tag: &str) {
+        self.tags.retain(|existing| !existing.eq_ignore_ascii_case(tag));
+    }
+
+    /// Completed and total subtasks, `None` if the task has no checklist.
+    pub fn subtask_progress(&self) -> Option<(usize, usize)> {
+        match self.subtasks.len() {
+            0 => None,
+            total => Some((self.subtasks.iter().filter(|subtask| subtask.completed).count(), total)),
+        }
+    }
+
+    /// Checks off every subtask, the task itself is completed through the toggle endpoint
+    /// so a recurring task gets its next instance.
+    pub fn complete_all_subtasks(&mut self) {
+        for subtask in self.subtasks.iter_mut() {
+            subtask.completed = true;
+        }
+    }
+
+    /// When the next instance of this recurring task will be due if it's completed at `completed_at`,
+    /// at the time of day this one is due, `None` if the task doesn't recur or its rule has ended.
+    /// The server creates that instance, this previews it.
+    pub fn next_due_at(&self, completed_at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let recurrence = self.recurrence.as_ref()?;
+        // the rule works on the days as the user sees them
+        let completed_at = completed_at.with_timezone(&Local).naive_local();
+        let time = match self.due_at() {
+            Some(due_at) => due_at.with_timezone(&Local).time(),
+            None => completed_at.time(),
+        };
+        let due_at = recurrence.next_occurrence(completed_at, time)?;
+        Some(Local.from_local_datetime(&due_at).earliest()?.with_timezone(&Utc))
+    }
+
+    pub fn due_state(&self, now: DateTime<Utc>) -> DueState {
+        let due_at = match (self.completed(), self.due_at()) {
+            (false, Some(due_at)) => due_at,
+            _ => return DueState::None,
+        };
+
+        if due_at <= now {
+            DueState::Overdue
+        }
+        else if due_at - now <= Duration::hours(DUE_SOON_HOURS) {
+            DueState::DueSoon
+        }
+        else {
+            DueState::Upcoming
+        }
+    }
 }
 
 impl From<RefCell<TodoTask>> for TodoTask {
@@ -77,6 +211,10 @@ impl From<RefCell<TodoTask>> for TodoTask {
             description: ref_cell.description.clone(),
             created_at: ref_cell.created_at.clone(),
             completed_at: ref_cell.completed_at.clone(),
+            due_at: ref_cell.due_at.clone(),
+            tags: ref_cell.tags.clone(),
+            subtasks: ref_cell.subtasks.clone(),
+            recurrence: ref_cell.recurrence.clone(),
             user_id: ref_cell.user_id.clone()
         }
     }
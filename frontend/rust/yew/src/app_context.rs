@@ -1,12 +1,16 @@
 use std::{collections::HashMap, rc::Rc};
 use yew::Reducible;
 
-use crate::styles::color::{Color, CssColor};
+use crate::{
+    api::api_config::ApiConfig,
+    styles::color::{Color, CssColor},
+};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct AppContext {
     theme: Theme,
-    themes_data: Vec<Theme>
+    themes_data: Vec<Theme>,
+    api_config: ApiConfig
 }
 
 impl Reducible for AppContext {
@@ -15,7 +19,8 @@ impl Reducible for AppContext {
     fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
         Self {
             theme: action,
-            themes_data: self.themes_data.clone()
+            themes_data: self.themes_data.clone(),
+            api_config: self.api_config.clone()
         }
         .into()
     }
@@ -26,7 +31,8 @@ impl Default for AppContext {
         let themes_data = Self::get_themes_data();
         Self {
             theme: themes_data[2].clone(),
-            themes_data
+            themes_data,
+            api_config: ApiConfig::get().clone()
         }
     }
 }
@@ -45,6 +51,10 @@ impl AppContext {
     pub fn get_themes(&self) -> &Vec<Theme> {
         &self.themes_data
     }
+
+    pub fn get_api_config(&self) -> &ApiConfig {
+        &self.api_config
+    }
     
     fn get_themes_data() -> Vec<Theme> {
         vec! [
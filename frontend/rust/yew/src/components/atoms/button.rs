@@ -12,7 +12,8 @@ pub struct ButtonProperties {
     pub back_color: Option<Color>,
     pub hover_color: Option<Color>,
     pub onclick: Option<Callback<MouseEvent>>,
-    pub data_test: Option<String>
+    pub data_test: Option<String>,
+    pub disabled: Option<bool>,
 }
 
 #[styled_component(Button)]
@@ -38,6 +39,12 @@ pub fn button(props: &ButtonProperties) -> Html {
             background-color: {hover_color};
         }}
 
+        :disabled {{
+            background-color: {background_color};
+            opacity: 0.5;
+            cursor: not-allowed;
+        }}
+
         @media only screen and (max-width: 650px) {{
             width: 100%;
         }}
@@ -53,7 +60,7 @@ pub fn button(props: &ButtonProperties) -> Html {
     let data_test = props.data_test.clone().unwrap_or_default();
     
     html! {
-        <button class={style} data-test={data_test} onclick={props.onclick.clone()}>
+        <button class={style} data-test={data_test} onclick={props.onclick.clone()} disabled={props.disabled.unwrap_or_default()}>
             {&props.label}
         </button>
     }
@@ -5,6 +5,8 @@ use yew::prelude::*;
 
 use crate::{styles::color::Color, app_context::AppContext};
 
+use super::field_error::FieldError;
+
 #[derive(PartialEq, Clone, Copy)]
 pub enum LabelLocation {
     Left,
@@ -27,6 +29,7 @@ pub struct CheckboxProperties {
     pub size: Option<String>, // TODO: use CssSize or whatever
     pub id: Option<String>,
     pub data_test: Option<String>,
+    pub error: Option<String>,
 }
 
 #[styled_component(Checkbox)]
@@ -65,6 +68,7 @@ pub fn checkbox(props: &CheckboxProperties) -> Html {
 
     let label_location = props.label_location.unwrap_or_default();
     html! {
+        <>
         <div class={main_style}>
         if label_location == LabelLocation::Left {
             if let Some(label) = props.label.clone() {
@@ -85,5 +89,7 @@ pub fn checkbox(props: &CheckboxProperties) -> Html {
             } 
         }
         </div>
+        <FieldError message={props.error.clone()} data_test={props.data_test.clone()}/>
+        </>
     }
 }
@@ -0,0 +1,100 @@
+use std::rc::Rc;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use stylist::{yew::styled_component, Style};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    styles::color::Color,
+    app_context::AppContext,
+    validation::{validate, DUE_AT_RULES},
+};
+
+use super::field_error::FieldError;
+
+/// The format of a `datetime-local` input's value.
+const INPUT_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+#[derive(Properties, PartialEq)]
+pub struct DateTimePickerProperties {
+    pub label: String,
+    pub value: Option<DateTime<Utc>>,
+    pub id: Option<String>,
+    /// Emits the picked moment, or `None` once the input is cleared.
+    pub onchange: Option<Callback<Option<DateTime<Utc>>>>,
+    pub data_test: Option<String>,
+    /// Shown instead of the picker's own error about a date that can't be read.
+    pub error: Option<String>,
+}
+
+/// Picks a date and time in the user's timezone.
+#[styled_component(DateTimePicker)]
+pub fn date_time_picker(props: &DateTimePickerProperties) -> Html {
+    let ctx: Rc<AppContext> = use_context().unwrap();
+    let input_style = Style::new(format!(
+        r#"
+        color: {primary_bg};
+        background-color: {secondary};
+        border-radius: 3px;
+        border: 1px solid transparent;
+        color-scheme: light dark;
+    "#,
+        primary_bg = Color::PrimaryBg.get_css_color(&ctx),
+        secondary = Color::Secondary.get_css_color(&ctx)
+    ))
+    .unwrap();
+
+    let label_style = Style::new(format!(
+        r#"
+        margin-bottom: 10px;
+        color: {};
+    "#,
+        Color::Primary.get_css_color(&ctx)
+    ))
+    .unwrap();
+
+    let main_style = Style::new(
+        r#"
+        display: flex;
+        flex-direction: column;
+    "#,
+    )
+    .unwrap();
+
+    let data_test = props.data_test.clone().unwrap_or_default();
+    let value = props
+        .value
+        .map(|value| value.with_timezone(&Local).format(INPUT_FORMAT).to_string())
+        .unwrap_or_default();
+
+    // an unreadable date is emitted as cleared, the error tells the user why
+    let invalid_error = use_state(|| None::<String>);
+    let onchange = props.onchange.clone().map(|onchange| {
+        let invalid_error = invalid_error.clone();
+        onchange.reform(move |event: Event| {
+            let value = event.target_unchecked_into::<HtmlInputElement>().value();
+            invalid_error.set(validate(&value, DUE_AT_RULES));
+            parse_local(&value)
+        })
+    });
+    let error = props.error.clone().or((*invalid_error).clone());
+
+    html! {
+        <div class={main_style}>
+            <label class={label_style}>{&props.label}</label>
+            <input class={input_style} id={props.id.clone()} type={"datetime-local"} {value} {onchange} data-test={data_test.clone()}/>
+            <FieldError message={error} {data_test}/>
+        </div>
+    }
+}
+
+/// The moment a `datetime-local` value stands for, `None` if it isn't one.
+pub fn parse_local(value: &str) -> Option<DateTime<Utc>> {
+    let naive = NaiveDateTime::parse_from_str(value, INPUT_FORMAT).ok()?;
+    // a time skipped by a DST change doesn't exist locally, the earlier reading of an ambiguous one is used
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+}
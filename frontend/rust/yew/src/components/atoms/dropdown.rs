@@ -5,6 +5,8 @@ use yew::prelude::*;
 
 use crate::{styles::color::Color, app_context::AppContext};
 
+use super::field_error::FieldError;
+
 #[derive(PartialEq, Clone)]
 pub struct DropdownOption {
     pub value: String,
@@ -18,7 +20,8 @@ pub struct DropdownProperties {
     pub selected_option: Option<DropdownOption>,
     pub onchange: Option<Callback<Event>>,
     pub id: Option<String>,
-    pub data_test: Option<String>
+    pub data_test: Option<String>,
+    pub error: Option<String>,
 }
 
 #[styled_component(Dropdown)]
@@ -77,9 +80,10 @@ pub fn dropdown(props: &DropdownProperties) -> Html {
     html! {
         <div class={main_style}>
             <label class={label_style}>{props.label.clone()}</label>
-            <select id={props.id.clone()} class={select_style} data-test={data_test} onchange={props.onchange.clone()}>
+            <select id={props.id.clone()} class={select_style} data-test={data_test.clone()} onchange={props.onchange.clone()}>
                 {for options}
             </select>
+            <FieldError message={props.error.clone()} {data_test}/>
         </div>
     }
 }
\ No newline at end of file
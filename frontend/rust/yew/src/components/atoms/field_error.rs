@@ -0,0 +1,35 @@
+use std::rc::Rc;
+
+use stylist::{yew::styled_component, Style};
+use yew::prelude::*;
+
+use crate::{styles::color::Color, app_context::AppContext};
+
+#[derive(Properties, PartialEq)]
+pub struct FieldErrorProperties {
+    pub message: Option<String>,
+    pub data_test: Option<String>,
+}
+
+/// The validation error shown under a form field, renders nothing while the field is valid.
+#[styled_component(FieldError)]
+pub fn field_error(props: &FieldErrorProperties) -> Html {
+    let ctx: Rc<AppContext> = use_context().unwrap();
+    let style = Style::new(format!(
+        r#"
+        margin: 5px 0 0 0;
+        font-size: 0.9em;
+        color: {};
+    "#,
+        Color::Error.get_css_color(&ctx)
+    ))
+    .unwrap();
+
+    let data_test = props.data_test.clone().map(|data_test| format!("{}-error", data_test));
+
+    html! {
+        if let Some(message) = props.message.clone() {
+            <p class={style} data-test={data_test}>{message}</p>
+        }
+    }
+}
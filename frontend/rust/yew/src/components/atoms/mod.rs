@@ -4,4 +4,7 @@ pub mod button;
 pub mod text_display;
 pub mod dropdown;
 pub mod checkbox;
-pub mod priority;
\ No newline at end of file
+pub mod priority;
+pub mod field_error;
+pub mod date_time_picker;
+pub mod tag_chip;
@@ -0,0 +1,85 @@
+use std::rc::Rc;
+
+use stylist::{yew::styled_component, Style};
+use yew::prelude::*;
+
+use crate::{styles::color::Color, app_context::AppContext};
+
+#[derive(Properties, PartialEq)]
+pub struct TagChipProperties {
+    pub tag: String,
+    /// Shows a remove button on the chip.
+    pub onremove: Option<Callback<String>>,
+    pub onclick: Option<Callback<String>>,
+    /// Unselected chips are dimmed, chips are selected unless told otherwise.
+    pub selected: Option<bool>,
+    /// Overrides the color the tag would get.
+    pub color: Option<Color>,
+    pub data_test: Option<String>,
+}
+
+#[styled_component(TagChip)]
+pub fn tag_chip(props: &TagChipProperties) -> Html {
+    let ctx: Rc<AppContext> = use_context().unwrap();
+    let selected = props.selected.unwrap_or(true);
+    let clickable = props.onclick.is_some();
+
+    let style = Style::new(format!(
+        r#"
+        display: inline-flex;
+        align-items: center;
+        gap: 0.25rem;
+        margin: 0.1rem;
+        padding: 0 0.5rem;
+        border-radius: 1rem;
+        font-size: 0.85em;
+        white-space: nowrap;
+        color: white;
+        background-color: {bg_color};
+        opacity: {opacity};
+        cursor: {cursor};
+        button {{
+            padding: 0;
+            border: none;
+            color: white;
+            background: none;
+            cursor: pointer;
+        }}
+        "#,
+        bg_color = props.color.clone().unwrap_or_else(|| tag_color(&props.tag)).get_css_color(&ctx),
+        opacity = if selected { "1" } else { "0.45" },
+        cursor = if clickable { "pointer" } else { "default" }
+    ))
+    .unwrap();
+
+    let onclick = props.onclick.clone().map(|onclick| {
+        let tag = props.tag.clone();
+        Callback::from(move |_: MouseEvent| onclick.emit(tag.clone()))
+    });
+    let onremove = props.onremove.clone().map(|onremove| {
+        let tag = props.tag.clone();
+        Callback::from(move |event: MouseEvent| {
+            event.prevent_default();
+            event.stop_propagation();
+            onremove.emit(tag.clone())
+        })
+    });
+
+    html! {
+        <span class={style} data-test={props.data_test.clone()} data-tag={props.tag.clone()} {onclick}>
+            {&props.tag}
+            if let Some(onremove) = onremove {
+                <button type={"button"} aria-label={format!("Remove {}", props.tag)} onclick={onremove}>{"×"}</button>
+            }
+        </span>
+    }
+}
+
+/// Every tag keeps the same color wherever it's shown.
+pub fn tag_color(tag: &str) -> Color {
+    let hash = tag
+        .to_lowercase()
+        .bytes()
+        .fold(0u32, |hash, byte| hash.wrapping_mul(31).wrapping_add(byte as u32));
+    Color::CustomStr(format!("hsl({}, 55%, 42%)", hash % 360))
+}
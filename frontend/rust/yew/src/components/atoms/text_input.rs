@@ -6,6 +6,8 @@ use yew::prelude::*;
 
 use crate::{styles::color::Color, app_context::AppContext};
 
+use super::field_error::FieldError;
+
 #[derive(PartialEq, Clone)]
 pub enum ControlType {
     Input,
@@ -21,8 +23,11 @@ pub struct TextInputProperties {
     pub placeholder: Option<String>,
     pub input_type: Option<String>,
     pub onchange: Option<Callback<Event>>,
+    /// Fires on every keystroke, for validating while the user types.
+    pub oninput: Option<Callback<InputEvent>>,
     pub rows: Option<i32>,
     pub data_test: Option<String>,
+    pub error: Option<String>,
 }
 
 #[styled_component(TextInput)]
@@ -101,12 +106,13 @@ pub fn text_input(props: &TextInputProperties) -> Html {
         <div class={main_style}>
             <label class={label_style}>{&props.label}</label>
             if control_type == ControlType::Input {
-                <input class={input_style} id={id.clone()} {placeholder} type={input_type} onchange={props.onchange.clone()} data-test={data_test} value={props.text.clone()}/>
+                <input class={input_style} id={id.clone()} {placeholder} type={input_type} onchange={props.onchange.clone()} oninput={props.oninput.clone()} data-test={data_test.clone()} value={props.text.clone()}/>
             }
             else{
-                <textarea class={input_style} id={id.clone()} onchange={props.onchange.clone()} data-test={data_test} rows={rows.to_string()}>
+                <textarea class={input_style} id={id.clone()} onchange={props.onchange.clone()} oninput={props.oninput.clone()} data-test={data_test.clone()} rows={rows.to_string()}>
                 </textarea>
             }
+            <FieldError message={props.error.clone()} {data_test}/>
         </div>
     }
 }
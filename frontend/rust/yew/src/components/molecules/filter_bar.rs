@@ -0,0 +1,273 @@
+use std::rc::Rc;
+
+use chrono::NaiveDate;
+use stylist::{yew::styled_component, Style};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    api::tasks::{
+        task_filter::{Combinator, DateField, FilterCondition, TaskFilter},
+        todo_task::Priority,
+    },
+    components::atoms::{
+        button::Button,
+        dropdown::{Dropdown, DropdownOption},
+        tag_chip::TagChip,
+        text_input::TextInput,
+    },
+    styles::color::Color, app_context::AppContext,
+};
+
+/// The format of a `date` input's value.
+const DATE_INPUT_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Properties, PartialEq)]
+pub struct FilterBarProperties {
+    pub filter: TaskFilter,
+    /// The tags the tasks can be filtered by.
+    pub tags: Vec<String>,
+    pub onchange: Callback<TaskFilter>,
+}
+
+/// Builds the filter of the task list, the active conditions are shown as chips that remove them.
+#[styled_component(FilterBar)]
+pub fn filter_bar(props: &FilterBarProperties) -> Html {
+    let ctx: Rc<AppContext> = use_context().unwrap();
+    let style = Style::new(format!(
+        r#"
+        display: flex;
+        flex-wrap: wrap;
+        align-items: flex-end;
+        gap: 1rem;
+        label {{
+            margin-bottom: 10px;
+            color: {primary};
+        }}
+        >div.chips {{
+            display: flex;
+            flex-direction: column;
+        }}
+        >div.chips >div {{
+            display: flex;
+            flex-wrap: wrap;
+            max-width: 20rem;
+        }}
+        >div.active {{
+            display: flex;
+            flex-wrap: wrap;
+            align-items: center;
+            gap: 0.25rem;
+            width: 100%;
+            color: {primary};
+        }}
+        "#,
+        primary = Color::Primary.get_css_color(&ctx)
+    ))
+    .unwrap();
+
+    // the date field the range inputs edit
+    let date_field = use_state(|| None::<DateField>);
+
+    // every control edits a copy of the current filter
+    let change = |edit: Rc<dyn Fn(&mut TaskFilter)>| {
+        let filter = props.filter.clone();
+        let onchange = props.onchange.clone();
+        move || {
+            let mut filter = filter.clone();
+            edit(&mut filter);
+            onchange.emit(filter);
+        }
+    };
+    let change_with_value = |edit: Rc<dyn Fn(&mut TaskFilter, String)>| {
+        let filter = props.filter.clone();
+        let onchange = props.onchange.clone();
+        move |value: String| {
+            let mut filter = filter.clone();
+            edit(&mut filter, value);
+            onchange.emit(filter);
+        }
+    };
+
+    let on_status_change = {
+        let change = change_with_value(Rc::new(|filter: &mut TaskFilter, value: String| {
+            filter.set_status(match value.as_str() {
+                "completed" => Some(true),
+                "incomplete" => Some(false),
+                _ => None,
+            })
+        }));
+        Callback::from(move |event: Event| change(event.target_unchecked_into::<HtmlInputElement>().value()))
+    };
+
+    let on_text_input = {
+        let change = change_with_value(Rc::new(|filter: &mut TaskFilter, value: String| filter.set_text(&value)));
+        Callback::from(move |event: InputEvent| change(event.target_unchecked_into::<HtmlInputElement>().value()))
+    };
+
+    let on_combinator_change = {
+        let change = change_with_value(Rc::new(|filter: &mut TaskFilter, value: String| {
+            filter.combinator = match value.as_str() {
+                "or" => Combinator::Or,
+                _ => Combinator::And,
+            }
+        }));
+        Callback::from(move |event: Event| change(event.target_unchecked_into::<HtmlInputElement>().value()))
+    };
+
+    let on_date_field_change = {
+        let date_field = date_field.clone();
+        let filter = props.filter.clone();
+        let onchange = props.onchange.clone();
+        Callback::from(move |event: Event| {
+            let value = event.target_unchecked_into::<HtmlInputElement>().value();
+            let field = DateField::ALL.into_iter().find(|field| field.label() == value);
+            date_field.set(field);
+            // picking a field alone keeps the tasks that have that date
+            if let Some(field) = field {
+                if filter.date_range(field).is_none() {
+                    let mut filter = filter.clone();
+                    filter.set(FilterCondition::DateRange { field, from: None, to: None });
+                    onchange.emit(filter);
+                }
+            }
+        })
+    };
+
+    let date_range_inputs = (*date_field).map(|field| {
+        let (from, to) = props.filter.date_range(field).unwrap_or_default();
+        let on_bound_change = |is_from: bool| {
+            let change = change_with_value(Rc::new(move |filter: &mut TaskFilter, value: String| {
+                let (mut from, mut to) = filter.date_range(field).unwrap_or_default();
+                let date = NaiveDate::parse_from_str(&value, DATE_INPUT_FORMAT).ok();
+                match is_from {
+                    true => from = date,
+                    false => to = date,
+                }
+                filter.set(FilterCondition::DateRange { field, from, to });
+            }));
+            Callback::from(move |event: Event| change(event.target_unchecked_into::<HtmlInputElement>().value()))
+        };
+        let format = |date: Option<NaiveDate>| date.map(|date| date.format(DATE_INPUT_FORMAT).to_string());
+        html! {
+            <>
+            <TextInput id={"filter-date-from"} label={"From"} input_type={"date"} text={format(from)} onchange={on_bound_change(true)} data_test={"filter-date-from"}/>
+            <TextInput id={"filter-date-to"} label={"To"} input_type={"date"} text={format(to)} onchange={on_bound_change(false)} data_test={"filter-date-to"}/>
+            </>
+        }
+    });
+
+    let priority_chips = [Priority::A, Priority::B, Priority::C].into_iter().map(|priority| {
+        let tag = priority.to_string();
+        let selected = props.filter.priorities().contains(&priority);
+        let toggle = change(Rc::new(move |filter: &mut TaskFilter| filter.toggle_priority(priority.clone())));
+        html! {
+            <TagChip {tag} {selected} color={Color::Highlight} onclick={Callback::from(move |_| toggle())} data_test={"filter-priority"}/>
+        }
+    });
+
+    let tag_chips = props.tags.iter().map(|tag| {
+        let selected = props.filter.has_tag(tag);
+        let toggle = change_with_value(Rc::new(|filter: &mut TaskFilter, tag: String| filter.toggle_tag(&tag)));
+        html! {
+            <TagChip key={tag.clone()} tag={tag.clone()} {selected} onclick={Callback::from(toggle)} data_test={"tag-filter-chip"}/>
+        }
+    });
+
+    let separator = match props.filter.combinator {
+        Combinator::And => "and",
+        Combinator::Or => "or",
+    };
+    let active_chips = props.filter.conditions.iter().enumerate().map(|(index, condition)| {
+        let remove = change(Rc::new(move |filter: &mut TaskFilter| filter.remove(index)));
+        html! {
+            <>
+            if index > 0 {
+                <span>{separator}</span>
+            }
+            <TagChip tag={condition.label()} color={Color::Highlight} onremove={Callback::from(move |_| remove())} data_test={"active-filter"}/>
+            </>
+        }
+    });
+    let clear = change(Rc::new(|filter: &mut TaskFilter| filter.clear()));
+
+    html! {
+        <div class={style} data-test={"filter"}>
+            <Dropdown label={"Status"} options={get_status_options()} selected_option={get_status_selected_option(props.filter.status())} onchange={on_status_change} data_test={"filter-status"}/>
+            <div class={"chips"}>
+                <label>{"Priority"}</label>
+                <div>{for priority_chips}</div>
+            </div>
+            <TextInput id={"filter-text"} label={"Search"} placeholder={"title or description.."} text={props.filter.text()} oninput={on_text_input} data_test={"filter-text"}/>
+            <Dropdown label={"Date"} options={get_date_field_options()} selected_option={get_date_field_selected_option(*date_field)} onchange={on_date_field_change} data_test={"filter-date-field"}/>
+            {for date_range_inputs}
+            if !props.tags.is_empty() {
+                <div class={"chips"} data-test={"tag-filter"}>
+                    <label>{"Tags"}</label>
+                    <div>{for tag_chips}</div>
+                </div>
+            }
+            <Dropdown label={"Match"} options={get_combinator_options()} selected_option={get_combinator_selected_option(props.filter.combinator)} onchange={on_combinator_change} data_test={"filter-combinator"}/>
+            if !props.filter.conditions.is_empty() {
+                <div class={"active"} data-test={"active-filters"}>
+                    {for active_chips}
+                    <Button label={"Clear filters"} onclick={Callback::from(move |_| clear())} data_test={"clear-filters"}/>
+                </div>
+            }
+        </div>
+    }
+}
+
+fn options(options: &[(&str, &str)]) -> Vec<DropdownOption> {
+    options
+        .iter()
+        .map(|(value, label)| DropdownOption {
+            value: value.to_string(),
+            label: Some(label.to_string()),
+        })
+        .collect()
+}
+
+fn get_status_options() -> Vec<DropdownOption> {
+    options(&[("any", "Any"), ("completed", "Completed"), ("incomplete", "Incomplete")])
+}
+
+fn get_status_selected_option(status: Option<bool>) -> Option<DropdownOption> {
+    let value = match status {
+        Some(true) => "completed",
+        Some(false) => "incomplete",
+        None => "any",
+    };
+    get_status_options().into_iter().find(|option| option.value == value)
+}
+
+fn get_date_field_options() -> Vec<DropdownOption> {
+    let mut result = vec![DropdownOption {
+        value: "-".to_string(),
+        label: None,
+    }];
+    for field in DateField::ALL {
+        result.push(DropdownOption {
+            value: field.label().to_string(),
+            label: None,
+        });
+    }
+    result
+}
+
+fn get_date_field_selected_option(field: Option<DateField>) -> Option<DropdownOption> {
+    let value = field.map_or("-", |field| field.label());
+    get_date_field_options().into_iter().find(|option| option.value == value)
+}
+
+fn get_combinator_options() -> Vec<DropdownOption> {
+    options(&[("and", "All conditions"), ("or", "Any condition")])
+}
+
+fn get_combinator_selected_option(combinator: Combinator) -> Option<DropdownOption> {
+    let value = match combinator {
+        Combinator::And => "and",
+        Combinator::Or => "or",
+    };
+    get_combinator_options().into_iter().find(|option| option.value == value)
+}
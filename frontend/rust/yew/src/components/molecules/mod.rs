@@ -1,2 +1,7 @@
 pub mod task;
-pub mod theme_selector;
\ No newline at end of file
+pub mod theme_selector;
+pub mod tag_input;
+pub mod tag_list;
+pub mod subtasks;
+pub mod recurrence_editor;
+pub mod filter_bar;
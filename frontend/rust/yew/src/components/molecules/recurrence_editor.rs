@@ -0,0 +1,172 @@
+use std::rc::Rc;
+
+use chrono::{Datelike, Local};
+use stylist::{yew::styled_component, Style};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    api::tasks::recurrence::{Frequency, RecurrenceRule, WEEKDAYS},
+    components::atoms::{
+        checkbox::{Checkbox, LabelLocation},
+        dropdown::{Dropdown, DropdownOption},
+        text_input::TextInput,
+    },
+    styles::color::Color, app_context::AppContext,
+};
+
+#[derive(Properties, PartialEq)]
+pub struct RecurrenceEditorProperties {
+    pub recurrence: Option<RecurrenceRule>,
+    /// Emits the edited rule, or `None` once the task no longer repeats.
+    pub onchange: Callback<Option<RecurrenceRule>>,
+    pub id: Option<String>,
+    pub data_test: Option<String>,
+}
+
+/// Picks how often a task repeats and until when, with the inputs the chosen frequency needs.
+#[styled_component(RecurrenceEditor)]
+pub fn recurrence_editor(props: &RecurrenceEditorProperties) -> Html {
+    let ctx: Rc<AppContext> = use_context().unwrap();
+    let style = Style::new(format!(
+        r#"
+        display: flex;
+        flex-direction: column;
+        gap: 0.5rem;
+        >div.weekdays {{
+            display: flex;
+            flex-wrap: wrap;
+            gap: 0.5rem;
+            color: {primary};
+        }}
+        "#,
+        primary = Color::Primary.get_css_color(&ctx)
+    ))
+    .unwrap();
+
+    let id = props.id.clone().unwrap_or("recurrence".to_string());
+    let data_test = props.data_test.clone().unwrap_or_default();
+
+    // every input edits a copy of the current rule
+    let change = |edit: Rc<dyn Fn(&mut RecurrenceRule, String) -> bool>| {
+        let recurrence = props.recurrence.clone();
+        let onchange = props.onchange.clone();
+        Callback::from(move |event: Event| {
+            let value = event.target_unchecked_into::<HtmlInputElement>().value();
+            if let Some(mut recurrence) = recurrence.clone() {
+                if edit(&mut recurrence, value) {
+                    onchange.emit(Some(recurrence));
+                }
+            }
+        })
+    };
+
+    let on_frequency_change = {
+        let recurrence = props.recurrence.clone();
+        let onchange = props.onchange.clone();
+        Callback::from(move |event: Event| {
+            let value = event.target_unchecked_into::<HtmlInputElement>().value();
+            let frequency = match value.as_str() {
+                "daily" => Frequency::Daily,
+                "weekly" => Frequency::Weekly { weekdays: vec![Local::now().weekday().num_days_from_monday()] },
+                "monthly" => Frequency::Monthly { day: Local::now().day() },
+                "every-n-days" => Frequency::EveryNDays { days: 2 },
+                _ => return onchange.emit(None),
+            };
+            let until = recurrence.as_ref().and_then(|recurrence| recurrence.until.clone());
+            onchange.emit(Some(RecurrenceRule { frequency, until }))
+        })
+    };
+
+    let on_day_change = change(Rc::new(|recurrence: &mut RecurrenceRule, value: String| {
+        match value.parse::<u32>() {
+            Ok(day) if (1..=31).contains(&day) => recurrence.frequency = Frequency::Monthly { day },
+            _ => return false,
+        };
+        true
+    }));
+
+    let on_days_change = change(Rc::new(|recurrence: &mut RecurrenceRule, value: String| {
+        match value.parse::<u32>() {
+            Ok(days) if days > 0 => recurrence.frequency = Frequency::EveryNDays { days },
+            _ => return false,
+        };
+        true
+    }));
+
+    let on_until_change = change(Rc::new(|recurrence: &mut RecurrenceRule, value: String| {
+        recurrence.until = match value.is_empty() {
+            true => None,
+            false => Some(value),
+        };
+        true
+    }));
+
+    let inputs = match props.recurrence.as_ref().map(|recurrence| &recurrence.frequency) {
+        Some(Frequency::Weekly { weekdays }) => {
+            let checkboxes = WEEKDAYS.iter().enumerate().map(|(index, name)| {
+                let index = index as u32;
+                let onchange = change(Rc::new(move |recurrence: &mut RecurrenceRule, _: String| {
+                    if let Frequency::Weekly { weekdays } = &mut recurrence.frequency {
+                        match weekdays.contains(&index) {
+                            true => weekdays.retain(|weekday| *weekday != index),
+                            false => {
+                                weekdays.push(index);
+                                weekdays.sort();
+                            }
+                        }
+                    }
+                    true
+                }));
+                html! {
+                    <Checkbox checked={weekdays.contains(&index)} label={name.to_string()} label_location={LabelLocation::Right} {onchange} data_test={format!("{}-weekday", data_test)}/>
+                }
+            });
+            html! { <div class={"weekdays"}>{for checkboxes}</div> }
+        }
+        Some(Frequency::Monthly { day }) => html! {
+            <TextInput id={format!("{}-day", id)} label={"Day of the month"} input_type={"number"} text={day.to_string()} onchange={on_day_change} data_test={format!("{}-day", data_test)}/>
+        },
+        Some(Frequency::EveryNDays { days }) => html! {
+            <TextInput id={format!("{}-days", id)} label={"Every how many days"} input_type={"number"} text={days.to_string()} onchange={on_days_change} data_test={format!("{}-days", data_test)}/>
+        },
+        _ => html! {},
+    };
+
+    html! {
+        <div class={style}>
+            <Dropdown id={format!("{}-frequency", id)} label={"Repeats"} options={get_frequency_options()} selected_option={get_selected_frequency(&props.recurrence)} onchange={on_frequency_change} data_test={format!("{}-frequency", data_test)}/>
+            {inputs}
+            if let Some(recurrence) = props.recurrence.as_ref() {
+                <TextInput id={format!("{}-until", id)} label={"Ends on"} input_type={"date"} text={recurrence.until.clone()} onchange={on_until_change} data_test={format!("{}-until", data_test)}/>
+            }
+        </div>
+    }
+}
+
+fn get_frequency_options() -> Vec<DropdownOption> {
+    [
+        ("never", "Never"),
+        ("daily", "Daily"),
+        ("weekly", "Weekly"),
+        ("monthly", "Monthly"),
+        ("every-n-days", "Every N days"),
+    ]
+    .iter()
+    .map(|(value, label)| DropdownOption {
+        value: value.to_string(),
+        label: Some(label.to_string()),
+    })
+    .collect()
+}
+
+fn get_selected_frequency(recurrence: &Option<RecurrenceRule>) -> Option<DropdownOption> {
+    let value = match recurrence.as_ref().map(|recurrence| &recurrence.frequency) {
+        None => "never",
+        Some(Frequency::Daily) => "daily",
+        Some(Frequency::Weekly { .. }) => "weekly",
+        Some(Frequency::Monthly { .. }) => "monthly",
+        Some(Frequency::EveryNDays { .. }) => "every-n-days",
+    };
+    get_frequency_options().into_iter().find(|option| option.value == value)
+}
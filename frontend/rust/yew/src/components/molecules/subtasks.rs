@@ -0,0 +1,181 @@
+use std::rc::Rc;
+
+use stylist::{yew::styled_component, Style};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    api::tasks::todo_task::Subtask,
+    components::atoms::checkbox::{Checkbox, LabelLocation},
+    styles::color::Color, app_context::AppContext,
+};
+
+fn get_subtasks_style(ctx: &AppContext) -> Style {
+    Style::new(format!(
+        r#"
+        display: flex;
+        flex-direction: column;
+        label {{
+            margin-bottom: 10px;
+            color: {primary};
+        }}
+        ol {{
+            margin: 0 0 0.5rem 0;
+            padding: 0;
+            list-style: none;
+        }}
+        li {{
+            display: flex;
+            align-items: center;
+            gap: 0.5rem;
+            margin-bottom: 0.25rem;
+        }}
+        li >div {{
+            flex: 1;
+        }}
+        input[type=text] {{
+            flex: 1;
+            color: {primary_bg};
+            background-color: {secondary};
+            border-radius: 3px;
+            border: 1px solid transparent;
+        }}
+        button {{
+            color: {secondary};
+            background: none;
+            border: none;
+            cursor: pointer;
+        }}
+        button:disabled {{
+            opacity: 0.3;
+            cursor: default;
+        }}
+        "#,
+        primary = Color::Primary.get_css_color(ctx),
+        primary_bg = Color::PrimaryBg.get_css_color(ctx),
+        secondary = Color::Secondary.get_css_color(ctx)
+    ))
+    .unwrap()
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SubtaskChecklistProperties {
+    pub subtasks: Vec<Subtask>,
+    /// Lets the user check items off by index, the list is read only without it.
+    pub ontoggle: Option<Callback<usize>>,
+    pub data_test: Option<String>,
+}
+
+/// The checklist of a task with its progress.
+#[styled_component(SubtaskChecklist)]
+pub fn subtask_checklist(props: &SubtaskChecklistProperties) -> Html {
+    let ctx: Rc<AppContext> = use_context().unwrap();
+    let style = get_subtasks_style(&ctx);
+    let data_test = props.data_test.clone().unwrap_or_default();
+    let completed = props.subtasks.iter().filter(|subtask| subtask.completed).count();
+
+    let items = props.subtasks.iter().enumerate().map(|(index, subtask)| {
+        let onclick = props.ontoggle.clone().map(|ontoggle| {
+            Callback::from(move |event: MouseEvent| {
+                event.prevent_default(); // the box is checked once the task is updated
+                ontoggle.emit(index)
+            })
+        });
+        html! {
+            <li data-test={format!("{}-item", data_test)}>
+                <Checkbox checked={subtask.completed} {onclick} label={subtask.title.clone()} label_location={LabelLocation::Right} data_test={format!("{}-completed", data_test)}/>
+            </li>
+        }
+    });
+
+    html! {
+        <div class={style} data-test={data_test.clone()}>
+            <label>{format!("Subtasks ({}/{})", completed, props.subtasks.len())}</label>
+            <ol>{for items}</ol>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SubtaskEditorProperties {
+    pub subtasks: Vec<Subtask>,
+    pub onchange: Callback<Vec<Subtask>>,
+    pub data_test: Option<String>,
+}
+
+/// Adds, renames, reorders, checks off and removes the items of a checklist.
+#[styled_component(SubtaskEditor)]
+pub fn subtask_editor(props: &SubtaskEditorProperties) -> Html {
+    let ctx: Rc<AppContext> = use_context().unwrap();
+    let style = get_subtasks_style(&ctx);
+    let data_test = props.data_test.clone().unwrap_or_default();
+    let count = props.subtasks.len();
+
+    // every change emits the whole list, edited by `edit`
+    let change = |edit: Rc<dyn Fn(&mut Vec<Subtask>)>| {
+        let subtasks = props.subtasks.clone();
+        let onchange = props.onchange.clone();
+        move || {
+            let mut subtasks = subtasks.clone();
+            edit(&mut subtasks);
+            onchange.emit(subtasks);
+        }
+    };
+
+    let items = props.subtasks.iter().enumerate().map(|(index, subtask)| {
+        let toggle = change(Rc::new(move |subtasks: &mut Vec<Subtask>| subtasks[index].completed = !subtasks[index].completed));
+        let move_up = change(Rc::new(move |subtasks: &mut Vec<Subtask>| subtasks.swap(index - 1, index)));
+        let move_down = change(Rc::new(move |subtasks: &mut Vec<Subtask>| subtasks.swap(index, index + 1)));
+        let remove = change(Rc::new(move |subtasks: &mut Vec<Subtask>| {
+            subtasks.remove(index);
+        }));
+        let rename = {
+            let subtasks = props.subtasks.clone();
+            let onchange = props.onchange.clone();
+            Callback::from(move |event: Event| {
+                let title = event.target_unchecked_into::<HtmlInputElement>().value();
+                let mut subtasks = subtasks.clone();
+                subtasks[index].title = title;
+                onchange.emit(subtasks);
+            })
+        };
+
+        html! {
+            <li data-test={format!("{}-item", data_test)}>
+                <Checkbox checked={subtask.completed} onchange={Callback::from(move |_| toggle())} data_test={format!("{}-completed", data_test)}/>
+                <input type={"text"} value={subtask.title.clone()} onchange={rename} data-test={format!("{}-title", data_test)}/>
+                <button type={"button"} aria-label={"Move up"} disabled={index == 0} onclick={Callback::from(move |_| move_up())}>{"▲"}</button>
+                <button type={"button"} aria-label={"Move down"} disabled={index + 1 == count} onclick={Callback::from(move |_| move_down())}>{"▼"}</button>
+                <button type={"button"} aria-label={"Remove"} onclick={Callback::from(move |_| remove())} data-test={format!("{}-remove", data_test)}>{"❌"}</button>
+            </li>
+        }
+    });
+
+    let onkeydown = {
+        let subtasks = props.subtasks.clone();
+        let onchange = props.onchange.clone();
+        Callback::from(move |event: KeyboardEvent| {
+            if event.key() != "Enter" {
+                return;
+            }
+            // Enter would submit the form the input is in
+            event.prevent_default();
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            let title = input.value().trim().to_string();
+            input.set_value("");
+            if !title.is_empty() {
+                let mut subtasks = subtasks.clone();
+                subtasks.push(Subtask { title, completed: false });
+                onchange.emit(subtasks);
+            }
+        })
+    };
+
+    html! {
+        <div class={style}>
+            <label>{"Subtasks"}</label>
+            <ol>{for items}</ol>
+            <input type={"text"} placeholder={"add a subtask and press Enter.."} {onkeydown} data-test={format!("{}-new", data_test)}/>
+        </div>
+    }
+}
@@ -0,0 +1,123 @@
+use std::rc::Rc;
+
+use stylist::{yew::styled_component, Style};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::atoms::tag_chip::TagChip,
+    styles::color::Color, app_context::AppContext,
+};
+
+#[derive(Properties, PartialEq)]
+pub struct TagInputProperties {
+    pub label: String,
+    pub tags: Vec<String>,
+    /// Offered while typing, tags the input already has are left out.
+    pub suggestions: Vec<String>,
+    pub onadd: Callback<String>,
+    pub onremove: Callback<String>,
+    pub id: Option<String>,
+    pub data_test: Option<String>,
+}
+
+/// Tags as removable chips, followed by an input that adds one on Enter, on a comma or when it loses focus.
+#[styled_component(TagInput)]
+pub fn tag_input(props: &TagInputProperties) -> Html {
+    let ctx: Rc<AppContext> = use_context().unwrap();
+    let main_style = Style::new(format!(
+        r#"
+        display: flex;
+        flex-direction: column;
+        label {{
+            margin-bottom: 10px;
+            color: {primary};
+        }}
+        >div {{
+            display: flex;
+            flex-wrap: wrap;
+            align-items: center;
+            gap: 0.25rem;
+            padding: 0.2rem;
+            border-radius: 3px;
+            background-color: {secondary};
+        }}
+        input {{
+            flex: 1;
+            min-width: 8rem;
+            color: {primary_bg};
+            background-color: transparent;
+            border: none;
+            outline: none;
+        }}
+        "#,
+        primary = Color::Primary.get_css_color(&ctx),
+        primary_bg = Color::PrimaryBg.get_css_color(&ctx),
+        secondary = Color::Secondary.get_css_color(&ctx)
+    ))
+    .unwrap();
+
+    let id = props.id.clone().unwrap_or("tags".to_string());
+    let list_id = format!("{}-suggestions", id);
+    let data_test = props.data_test.clone().unwrap_or_default();
+
+    let onkeydown = {
+        let onadd = props.onadd.clone();
+        let onremove = props.onremove.clone();
+        let last_tag = props.tags.last().cloned();
+        Callback::from(move |event: KeyboardEvent| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            match event.key().as_str() {
+                // Enter would submit the form the input is in
+                "Enter" | "," => {
+                    event.prevent_default();
+                    add_tag(&input, &onadd);
+                }
+                "Backspace" if input.value().is_empty() => {
+                    if let Some(tag) = last_tag.clone() {
+                        onremove.emit(tag);
+                    }
+                }
+                _ => (),
+            }
+        })
+    };
+
+    let onchange = {
+        let onadd = props.onadd.clone();
+        Callback::from(move |event: Event| add_tag(&event.target_unchecked_into::<HtmlInputElement>(), &onadd))
+    };
+
+    let chips = props.tags.iter().map(|tag| {
+        html! {
+            <TagChip key={tag.clone()} tag={tag.clone()} onremove={props.onremove.clone()} data_test={format!("{}-chip", data_test)}/>
+        }
+    });
+
+    let suggestions = props
+        .suggestions
+        .iter()
+        .filter(|suggestion| !props.tags.iter().any(|tag| tag.eq_ignore_ascii_case(suggestion)))
+        .map(|suggestion| html! { <option value={suggestion.clone()}/> });
+
+    html! {
+        <div class={main_style}>
+            <label for={id.clone()}>{&props.label}</label>
+            <div>
+                {for chips}
+                <input id={id} list={list_id.clone()} placeholder={"add a tag.."} {onkeydown} {onchange} data-test={data_test.clone()}/>
+                <datalist id={list_id}>
+                    {for suggestions}
+                </datalist>
+            </div>
+        </div>
+    }
+}
+
+fn add_tag(input: &HtmlInputElement, onadd: &Callback<String>) {
+    let tag = input.value().trim().trim_end_matches(',').trim().to_string();
+    input.set_value("");
+    if !tag.is_empty() {
+        onadd.emit(tag);
+    }
+}
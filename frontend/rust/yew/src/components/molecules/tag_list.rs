@@ -0,0 +1,50 @@
+use std::rc::Rc;
+
+use stylist::{yew::styled_component, Style};
+use yew::prelude::*;
+
+use crate::{
+    components::atoms::tag_chip::TagChip,
+    styles::color::Color, app_context::AppContext,
+};
+
+#[derive(Properties, PartialEq)]
+pub struct TagListProperties {
+    pub tags: Vec<String>,
+    pub label: Option<String>,
+    pub data_test: Option<String>,
+}
+
+#[styled_component(TagList)]
+pub fn tag_list(props: &TagListProperties) -> Html {
+    let ctx: Rc<AppContext> = use_context().unwrap();
+    let style = Style::new(format!(
+        r#"
+        display: flex;
+        flex-wrap: wrap;
+        align-items: center;
+        label {{
+            margin-right: 10px;
+            color: {};
+        }}
+        "#,
+        Color::Primary.get_css_color(&ctx)
+    ))
+    .unwrap();
+
+    let data_test = props.data_test.clone().unwrap_or_default();
+    let chips = props.tags.iter().map(|tag| {
+        html! {
+            <TagChip key={tag.clone()} tag={tag.clone()} data_test={format!("{}-chip", data_test)}/>
+        }
+    });
+
+    html! {
+        <div class={style} data-test={data_test.clone()}>
+            if let Some(label) = props.label.clone() {
+                <label>{label}</label>
+            }
+            {for chips}
+        </div>
+    }
+}
@@ -1,18 +1,23 @@
 use std::rc::Rc;
 
+use chrono::{Local, Utc};
 use stylist::{style, yew::styled_component, Style};
 use yew::prelude::*;
+use yewdux::prelude::use_selector_with_deps;
 
 use crate::{
-    api::tasks::todo_task::TodoTask,
-    components::atoms::{checkbox::Checkbox, route_link::RouteLink, priority::Priority},
+    api::tasks::todo_task::DueState,
+    components::{
+        atoms::{checkbox::Checkbox, route_link::RouteLink, priority::Priority},
+        molecules::tag_list::TagList,
+    },
     router::Route,
-    styles::color::Color, app_context::AppContext,
+    styles::color::Color, app_context::AppContext, TaskStore,
 };
 
 #[derive(Properties, PartialEq)]
 pub struct TaskProperties {
-    pub todo_task: TodoTask,
+    pub task_id: i32,
     pub remove_onclick: Callback<MouseEvent>,
     pub toggle_completed: Callback<MouseEvent>,
 }
@@ -20,7 +25,11 @@ pub struct TaskProperties {
 #[styled_component(Task)]
 pub fn task(props: &TaskProperties) -> Html {
     let ctx: Rc<AppContext> = use_context().unwrap();
-    let task = &props.todo_task;
+    let task = use_selector_with_deps(|store: &TaskStore, id| store.get_task(*id).cloned(), props.task_id);
+    let task = match task.as_ref() {
+        Some(task) => task,
+        None => return html! {},
+    };
     let mut hide_completion = false;
     let (creation_time, creation_date) = match task.created_at() {
         Some(datetime) => (datetime.time().format("%H:%M").to_string(), datetime.date().format("%d/%m/%y").to_string()),
@@ -34,6 +43,18 @@ pub fn task(props: &TaskProperties) -> Html {
         } 
     };
 
+    let due_state = task.due_state(Utc::now());
+    let due_at = task
+        .due_at()
+        .map(|datetime| datetime.with_timezone(&Local).format("%H:%M %d/%m/%y").to_string());
+    // overdue and soon to be due tasks stand out with the theme's warning colors
+    let (shadow_color, due_color, due_label) = match due_state {
+        DueState::Overdue => (Color::Error, Color::Error, "overdue"),
+        DueState::DueSoon => (Color::Highlight, Color::Highlight, "due-soon"),
+        DueState::Upcoming => (Color::Highlight2, Color::Secondary, "upcoming"),
+        DueState::None => (Color::Highlight2, Color::Secondary, "none"),
+    };
+
     let task_style = Style::new(format!(
         r#"
         margin: 1rem;
@@ -43,12 +64,12 @@ pub fn task(props: &TaskProperties) -> Html {
         max-width: 450px;
         border-radius: 20px;
         box-sizing: border-box;
-        box-shadow: 8px 8px 5px {highlight2};
+        box-shadow: 8px 8px 5px {shadow};
         @media only screen and (max-width: 850px) {{
             width: 100%;
         }}
         "#,
-        highlight2 = Color::Highlight2.get_css_color(&ctx)
+        shadow = shadow_color.get_css_color(&ctx)
     ))
     .unwrap();
     let up_style = Style::new(format!(
@@ -79,7 +100,7 @@ pub fn task(props: &TaskProperties) -> Html {
         padding : 0.5rem;
         padding-top: 0;
         display: flex;
-        height: calc(max(1.5vh, 0.75em, 0.75rem) * 6 - 2px);
+        min-height: calc(max(1.5vh, 0.75em, 0.75rem) * 6 - 2px);
         border: solid {secondary} 2px;
         border-bottom-left-radius: 20px;
         border-bottom-right-radius: 20px;
@@ -143,6 +164,26 @@ pub fn task(props: &TaskProperties) -> Html {
         "#
     )
     .unwrap();
+    // the card grows with its details instead of letting them spill over the card below
+    let details_style = style!(
+        r#"
+        justify-content: flex-start !important;
+        min-width: 0;
+        overflow: hidden;
+        "#
+    )
+    .unwrap();
+
+    let due_style = Style::new(format!(
+        r#"
+        margin: 0 0.25rem;
+        font-weight: bold;
+        color: {} !important;
+        "#,
+        due_color.get_css_color(&ctx)
+    ))
+    .unwrap();
+
     let remove_onclick = props.remove_onclick.clone();
     let toggle_completed = props.toggle_completed.clone();
     let priority = match &task.priority {
@@ -150,7 +191,7 @@ pub fn task(props: &TaskProperties) -> Html {
         None => "-".to_string()
     };
     html! {
-        <div class={task_style}>
+        <div class={task_style} data-due={due_label}>
             <div class={up_style}>
                 <div>
                     <Checkbox data_test={"completed"} checked={task.completed()} onclick={toggle_completed} size={"calc(max(2vh, 1em, 1rem) * 1.25)"}/>
@@ -187,8 +228,22 @@ pub fn task(props: &TaskProperties) -> Html {
                 }
                 </div>
             </div>
+            <div class={details_style}>
+                if let Some(due_at) = due_at {
+                    <p class={due_style} data-test={"due-at"}>{format!("⏰ {}", due_at)}</p>
+                }
+                if let Some((completed, total)) = task.subtask_progress() {
+                    <p style={"margin: 0 0.25rem;"} data-test={"subtask-progress"}>{format!("☑ {}/{}", completed, total)}</p>
+                }
+                if let Some(recurrence) = task.recurrence.as_ref() {
+                    <p style={"margin: 0 0.25rem;"} title={recurrence.to_string()} data-test={"recurrence"}>{"🔁"}</p>
+                }
+                if !task.tags.is_empty() {
+                    <TagList tags={task.tags.clone()} data_test={"tags"}/>
+                }
                 <p style={"margin-left: 0.25rem;"}>{task.description.as_ref().unwrap_or(&"Go to task details!".to_string())}</p>
             </div>
+            </div>
         </div>
     }
 }
@@ -0,0 +1,70 @@
+use std::rc::Rc;
+
+use stylist::{yew::styled_component, Style};
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+use crate::{
+    api::tasks::task_events::{ConnectionState, TaskEvent, TaskEventsClient},
+    app_context::AppContext,
+    SessionStore, TaskStore,
+};
+
+/// Keeps the task store up to date with changes pushed by the server,
+/// for backends that provide a task event stream.
+#[styled_component(LiveUpdates)]
+pub fn live_updates() -> Html {
+    let ctx: Rc<AppContext> = use_context().unwrap();
+    let token = use_selector(|store: &SessionStore| store.user.as_ref().map(|user| user.token.clone()));
+    let connection_state = use_state(|| None::<ConnectionState>);
+    let enabled = ctx.get_api_config().live_updates;
+
+    {
+        let connection_state = connection_state.clone();
+        use_effect_with_deps(
+            move |(token, enabled)| {
+                let client = match (token.as_ref(), enabled) {
+                    (Some(token), true) => {
+                        let on_event = Callback::from(|event: TaskEvent| {
+                            Dispatch::<TaskStore>::new().reduce_mut(|store| match event {
+                                // completing a recurring task sends its next instance as created
+                                TaskEvent::Created(task) | TaskEvent::Updated(task) => store.upsert_task(task),
+                                TaskEvent::Deleted(task_id) => {
+                                    store.remove_task(task_id);
+                                }
+                            })
+                        });
+                        let on_state = {
+                            let connection_state = connection_state.clone();
+                            Callback::from(move |state| connection_state.set(Some(state)))
+                        };
+                        Some(TaskEventsClient::connect(token, on_event, on_state))
+                    }
+                    _ => None,
+                };
+                move || {
+                    drop(client);
+                    connection_state.set(None);
+                }
+            },
+            ((*token).clone(), enabled),
+        );
+    }
+
+    let style = Style::new(
+        r#"
+        text-align: center;
+        margin: 0.25rem 0;
+        "#,
+    )
+    .unwrap();
+
+    match *connection_state {
+        Some(ConnectionState::Reconnecting { delay_ms }) => html! {
+            <p class={style} data-test={"live-updates"}>
+                {format!("Lost the connection for live updates, reconnecting in {} s", (delay_ms + 999) / 1000)}
+            </p>
+        },
+        _ => html! {},
+    }
+}
@@ -1,3 +1,7 @@
 pub mod navbar;
 pub mod tasks;
-pub mod error_message;
\ No newline at end of file
+pub mod error_message;
+pub mod outbox_status;
+pub mod task_merge_view;
+pub mod live_updates;
+pub mod session_expiry;
\ No newline at end of file
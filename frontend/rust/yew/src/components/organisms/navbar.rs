@@ -2,12 +2,17 @@ use std::rc::Rc;
 
 use lazy_static::__Deref;
 use stylist::yew::styled_component;
+use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
-use yewdux::prelude::use_store;
+use yewdux::prelude::*;
 
+use crate::api::auth::auth_service::AuthService;
 use crate::app_context::AppContext;
-use crate::{SessionStore, TaskStore};
+use crate::utils::handle_api_error;
+use crate::{SessionStore, TaskStore, drafts::DraftStore, outbox::OutboxStore, task_cache::TaskCache};
 use crate::components::atoms::route_link::RouteLink;
+use crate::components::organisms::error_message::ErrorMessage;
+use crate::components::pages::error_data::ErrorData;
 use crate::router::Route;
 use crate::styles::color::Color;
 use crate::styles::styles::Styles;
@@ -25,23 +30,44 @@ pub fn navbar(props: &NavbarProperties) -> Html {
     let (_, task_dispatch) = use_store::<TaskStore>();
     let (session_store, session_dispatch) = use_store::<SessionStore>();
     let (style, div_style) = Styles::get_navbar_styles(&ctx, props.fore_color.as_ref(), props.back_color.as_ref());
+    let error_data = use_state(|| ErrorData::default());
 
     let logout = {
         let session_dispatch = session_dispatch.clone();
         let task_dispatch = task_dispatch.clone();
+        let user = session_store.user.clone();
+        let error_data = error_data.clone();
         Callback::from(move |_: MouseEvent| {
-            task_dispatch.reduce(|_| {
-                TaskStore::default().into()
-            });
-            session_dispatch.reduce(|session_store| {
-                let mut session_store = session_store.deref().clone();
-                session_store.user = None;
-                session_store.into()
+            let session_dispatch = session_dispatch.clone();
+            let task_dispatch = task_dispatch.clone();
+            let user = user.clone();
+            let error_data = error_data.clone();
+            spawn_local(async move {
+                if let Some(user) = user {
+                    // the token is cleared locally even if the server failed to revoke it
+                    if let Err(error) = AuthService::logout(user.token).await {
+                        handle_api_error(error, &session_dispatch, Some(error_data));
+                    }
+                    TaskCache::clear(user.id).await;
+                    Dispatch::<OutboxStore>::new().reduce_mut(|outbox| outbox.clear(user.id));
+                    Dispatch::<DraftStore>::new().reduce_mut(|drafts| drafts.clear());
+                }
+                task_dispatch.reduce(|_| {
+                    TaskStore::default().into()
+                });
+                session_dispatch.reduce(|session_store| {
+                    let mut session_store = session_store.deref().clone();
+                    session_store.user = None;
+                    // logging out on purpose isn't a session to come back to
+                    session_store.return_to = None;
+                    session_store.into()
+                });
             });
         })
     };
 
     html! {
+        <>
         <section class={style}>
             <div class={div_style.clone()}>
                 <RouteLink
@@ -81,5 +107,9 @@ pub fn navbar(props: &NavbarProperties) -> Html {
                 </div>
             }
         </section>
+        if error_data.display {
+            <ErrorMessage message={error_data.message.clone()} data_test={"logout-error"}/>
+        }
+        </>
     }
 }
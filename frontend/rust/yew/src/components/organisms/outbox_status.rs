@@ -0,0 +1,88 @@
+use std::rc::Rc;
+
+use gloo::{events::EventListener, utils::window};
+use stylist::{yew::styled_component, Style};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+use crate::{
+    app_context::AppContext,
+    components::atoms::button::Button,
+    outbox::{is_offline, replay_outbox, OutboxStore},
+    styles::color::Color,
+    SessionStore,
+};
+
+/// Replays the outbox whenever the connection comes back,
+/// and tells the user about changes that are still waiting or were rejected.
+#[styled_component(OutboxStatus)]
+pub fn outbox_status() -> Html {
+    let ctx: Rc<AppContext> = use_context().unwrap();
+    let (session_store, _) = use_store::<SessionStore>();
+    let (outbox_store, outbox_dispatch) = use_store::<OutboxStore>();
+    let user = session_store.user.clone();
+
+    use_effect_with_deps(
+        move |user| {
+            // operations left over from a previous visit are sent right away if we're online
+            let listener = user.clone().map(|user| {
+                if !is_offline() {
+                    spawn_local(replay_outbox(user.clone()));
+                }
+                EventListener::new(&window(), "online", move |_| spawn_local(replay_outbox(user.clone())))
+            });
+            move || drop(listener)
+        },
+        user.clone(),
+    );
+
+    let pending = match &user {
+        Some(user) => outbox_store.pending(user.id).len(),
+        None => 0,
+    };
+    let conflicts = outbox_store.conflicts().to_vec();
+    let dismiss_conflicts = Callback::from(move |_: MouseEvent| {
+        outbox_dispatch.reduce_mut(|outbox| outbox.dismiss_conflicts())
+    });
+
+    let style = Style::new(format!(
+        r#"
+        display: flex;
+        flex-flow: column;
+        align-items: center;
+        text-align: center;
+        p {{
+            margin: 0.25rem 0;
+        }}
+        .conflict {{
+            width: 100%;
+            padding: 5px 0px;
+            color: white;
+            background-color: {error};
+        }}
+        "#,
+        error = Color::Error.get_css_color(&ctx),
+    ))
+    .unwrap();
+
+    if pending == 0 && conflicts.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <div class={style}>
+            if pending > 0 {
+                <p data-test={"outbox-pending"}>
+                    {format!("{} change(s) made offline, waiting to be synced", pending)}
+                </p>
+            }
+            if !conflicts.is_empty() {
+                {for conflicts.iter().map(|conflict| html! {
+                    <p class={"conflict"} data-test={"outbox-conflict"}>{conflict}</p>
+                })}
+                <Button label={"Dismiss"} onclick={dismiss_conflicts} data_test={"dismiss-conflicts"}/>
+            }
+        </div>
+    }
+}
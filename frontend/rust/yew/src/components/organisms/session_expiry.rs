@@ -0,0 +1,127 @@
+use std::rc::Rc;
+
+use chrono::Utc;
+use gloo::{console::log, timers::callback::Timeout};
+use stylist::{yew::styled_component, Style};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+use crate::{
+    api::auth::auth_service::AuthService,
+    app_context::AppContext,
+    components::atoms::button::Button,
+    router::redirect_to_login,
+    styles::color::Color,
+    utils::end_session,
+    SessionStore,
+};
+
+/// How long before the session expires the user is warned, or it's renewed.
+const SESSION_WARNING_MS: i64 = 2 * 60 * 1000;
+
+/// Warns the user before their session expires and renews it where the backend allows it.
+/// Once the session has ended, the user is taken to the login page to continue where they were.
+#[styled_component(SessionExpiry)]
+pub fn session_expiry() -> Html {
+    let ctx: Rc<AppContext> = use_context().unwrap();
+    let (session_store, session_dispatch) = use_store::<SessionStore>();
+    let navigator = use_navigator().unwrap();
+    let expiring = use_state(|| false);
+    let renewal = ctx.get_api_config().session_renewal;
+    let user = session_store.user.clone();
+
+    {
+        let expiring = expiring.clone();
+        let session_dispatch = session_dispatch.clone();
+        use_effect_with_deps(
+            move |user| {
+                expiring.set(false);
+                let timers = user.clone().and_then(|user| {
+                    let ms_left = (user.expires_at()? - Utc::now()).num_milliseconds();
+                    let warning = {
+                        let session_dispatch = session_dispatch.clone();
+                        Timeout::new(timeout_ms(ms_left - SESSION_WARNING_MS), move || match renewal {
+                            true => renew_session(user.token, session_dispatch, expiring),
+                            false => expiring.set(true),
+                        })
+                    };
+                    let expiry = Timeout::new(timeout_ms(ms_left), move || end_session(&session_dispatch));
+                    Some((warning, expiry))
+                });
+                move || drop(timers)
+            },
+            user.clone(),
+        );
+    }
+
+    let session_ended = user.is_none() && session_store.return_to.is_some();
+    {
+        let return_to = session_store.return_to.clone();
+        use_effect_with_deps(
+            move |session_ended| {
+                if *session_ended {
+                    redirect_to_login(&navigator, return_to);
+                }
+                || ()
+            },
+            session_ended,
+        );
+    }
+
+    let style = Style::new(format!(
+        r#"
+        display: flex;
+        flex-flow: column;
+        align-items: center;
+        text-align: center;
+        padding: 5px 0px;
+        border-bottom: 2px solid {warning};
+        p {{
+            margin: 0.25rem 0;
+        }}
+        "#,
+        warning = Color::Error.get_css_color(&ctx),
+    ))
+    .unwrap();
+
+    let user = match (user, *expiring) {
+        (Some(user), true) => user,
+        _ => return html! {},
+    };
+
+    let onclick = match renewal {
+        true => Callback::from(move |_: MouseEvent| renew_session(user.token.clone(), session_dispatch.clone(), expiring.clone())),
+        false => Callback::from(move |_: MouseEvent| end_session(&session_dispatch)),
+    };
+    let label = match renewal {
+        true => "Stay logged in",
+        false => "Log in again",
+    };
+
+    html! {
+        <div class={style} data-test={"session-expiring"}>
+            <p>{"Your session is about to expire. You can continue where you left off after logging in again."}</p>
+            <Button {label} {onclick} data_test={"session-renew"}/>
+        </div>
+    }
+}
+
+/// Delays past what `setTimeout` can handle would fire right away, so they are capped.
+fn timeout_ms(ms: i64) -> u32 {
+    ms.clamp(0, i32::MAX as i64) as u32
+}
+
+fn renew_session(token: String, session_dispatch: Dispatch<SessionStore>, expiring: UseStateHandle<bool>) {
+    spawn_local(async move {
+        match AuthService::renew(token).await {
+            Ok(auth) => session_dispatch.reduce_mut(|store| store.user = Some(auth)),
+            // the session still works until it expires, so the user only gets warned
+            Err(error) => {
+                log!(format!("Unable to renew the session: {}", error));
+                expiring.set(true);
+            }
+        }
+    });
+}
@@ -0,0 +1,80 @@
+use std::rc::Rc;
+
+use stylist::yew::styled_component;
+use yew::prelude::*;
+
+use crate::{
+    api::tasks::task_merge::{TaskField, TaskMerge},
+    app_context::AppContext,
+    components::atoms::button::Button,
+    styles::{color::Color, styles::Styles},
+};
+
+#[derive(Properties, PartialEq)]
+pub struct TaskMergeViewProperties {
+    pub merge: TaskMerge,
+    /// Called with the field and whether the local value should be kept.
+    pub on_resolve: Callback<(TaskField, bool)>,
+    pub on_save: Callback<MouseEvent>,
+    pub on_discard: Callback<MouseEvent>,
+}
+
+/// Lets the user pick between their value and the server's for every conflicting field.
+#[styled_component(TaskMergeView)]
+pub fn task_merge_view(props: &TaskMergeViewProperties) -> Html {
+    let ctx: Rc<AppContext> = use_context().unwrap();
+    let (table_style, button_style) = Styles::get_table_style(&ctx);
+    let merge = &props.merge;
+
+    let rows = merge.conflicts.iter().map(|field| {
+        let field = *field;
+        let keeps_local = merge.keeps_local(field);
+        let name = format!("merge-{}", field.label().to_lowercase());
+        let keep_local = props.on_resolve.reform(move |_: Event| (field, true));
+        let keep_remote = props.on_resolve.reform(move |_: Event| (field, false));
+        html! {
+            <tr data-test={name.clone()}>
+                <td>{field.label()}</td>
+                <td>{field.display(&merge.base)}</td>
+                <td>
+                    <label>
+                        <input type="radio" name={name.clone()} data-test={"keep-local"} checked={keeps_local} onchange={keep_local}/>
+                        {field.display(&merge.local)}
+                    </label>
+                </td>
+                <td>
+                    <label>
+                        <input type="radio" name={name} data-test={"keep-remote"} checked={!keeps_local} onchange={keep_remote}/>
+                        {field.display(&merge.remote)}
+                    </label>
+                </td>
+            </tr>
+        }
+    });
+
+    html! {
+        <div class={table_style} data-test={"merge-view"}>
+            <h4>{"This task was changed somewhere else while you were editing it"}</h4>
+            <p>{"Changes that don't overlap were merged, choose which version to keep for the rest."}</p>
+            <table>
+                <tr>
+                    <th>{"Field"}</th>
+                    <th>{"When you started"}</th>
+                    <th>{"Yours"}</th>
+                    <th>{"On the server"}</th>
+                </tr>
+                {for rows}
+            </table>
+            <div class={button_style}>
+                <Button
+                    label={"Discard my changes"}
+                    fore_color={Color::CustomStr("white".to_string())}
+                    back_color={Color::Error}
+                    hover_color={Color::Error2}
+                    data_test={"merge-discard"}
+                    onclick={props.on_discard.clone()}/>
+                <Button label={"Save merged task"} onclick={props.on_save.clone()} data_test={"merge-save"}/>
+            </div>
+        </div>
+    }
+}
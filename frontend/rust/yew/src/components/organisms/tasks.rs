@@ -1,40 +1,38 @@
-use lazy_static::__Deref;
+use chrono::{DateTime, Utc};
+use gloo::{
+    events::EventListener,
+    timers::callback::Interval,
+    utils::{document, window},
+};
 use std::{rc::Rc, cmp::Ordering};
 use stylist::style;
 use wasm_bindgen_futures::spawn_local;
-use web_sys::HtmlInputElement;
+use web_sys::{AbortController, AbortSignal, HtmlInputElement};
 use yew::prelude::*;
 use yew_router::prelude::*;
 use yewdux::prelude::*;
 
 use crate::{
-    api::tasks::{todo_task::{TodoTask, Priority}, tasks_service::TasksService},
+    api::{api_client::Conditional, tasks::{task_filter::TaskFilter, todo_task::TodoTask, tasks_service::TasksService}},
     components::{atoms::{
         button::Button,
         dropdown::{Dropdown, DropdownOption},
     },
-    molecules::task::Task,
+    molecules::{filter_bar::FilterBar, task::Task},
     pages::error_data::ErrorData},
     router::Route,
     styles::styles::Styles,
-    SessionStore, TaskStore, utils::handle_api_error, app_context::AppContext,
+    SessionStore, TaskStore, task_cache::TaskCache, task_store::FetchState,
+    outbox::{apply_pending, defer_operation, is_offline, is_offline_error, queue_operation, should_queue, PendingOperation},
+    utils::{handle_api_error, ErrorAction}, app_context::AppContext,
 };
 
-#[derive(Clone, Copy)]
-enum FilterMode {
-    None,
-    CompletedTasks,
-    IncompletedTasks,
-    PriorityA,
-    PriorityB,
-    PriorityC,
-}
-
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 enum SortMode {
     Title,
     Priority,
-    Created
+    Created,
+    DueDate,
 }
 
 #[derive(PartialEq, Properties)]
@@ -46,7 +44,11 @@ pub struct TasksProperties {
 pub fn tasks(props: &TasksProperties) -> Html {
     let ctx = use_context::<Rc<AppContext>>().unwrap();
     let (session_store, session_dispatch) = use_store::<SessionStore>();
-    let (task_store, task_dispatch) = use_store::<TaskStore>();
+    let task_dispatch = Dispatch::<TaskStore>::new();
+    let list_version = use_selector(|store: &TaskStore| store.list_version());
+    let list_status = use_selector(|store: &TaskStore| {
+        (store.has_list(), store.list_state(), store.stale_since().cloned())
+    });
     let history = use_navigator().unwrap();
 
     let token = match session_store.user.clone() {
@@ -61,55 +63,74 @@ pub fn tasks(props: &TasksProperties) -> Html {
             }
     });
 
-    if let Some(token) = token.clone() {
-        let task_store = task_store.clone();
+    {
         let task_dispatch = task_dispatch.clone();
         let session_dispatch = session_dispatch.clone();
-        update_tasks_in_store(token, task_store, task_dispatch, session_dispatch, props.error_data.clone());
+        let error_data = props.error_data.clone();
+        use_effect_with_deps(
+            move |(token, _)| {
+                // cancels the pending fetch once the list is unmounted or has to be fetched again
+                let controller = AbortController::new().ok();
+                if let Some(token) = token.clone() {
+                    let abort_signal = controller.as_ref().map(|controller| controller.signal());
+                    update_tasks_in_store(token, task_dispatch, session_dispatch, error_data, abort_signal);
+                }
+                move || {
+                    if let Some(controller) = controller {
+                        controller.abort();
+                    }
+                }
+            },
+            (token.clone(), *list_version),
+        );
     }
 
-    let mut tasks: Vec<TodoTask> = Vec::new();
-
-    if let Some(new_tasks) = task_store.deref().clone().tasks {
-        tasks = new_tasks;
+    {
+        let task_dispatch = task_dispatch.clone();
+        let session_dispatch = session_dispatch.clone();
+        use_effect_with_deps(
+            move |(token, poll_interval_secs)| {
+                let watcher = token
+                    .clone()
+                    .map(|token| watch_tasks(token, *poll_interval_secs, task_dispatch, session_dispatch));
+                move || drop(watcher)
+            },
+            (token.clone(), ctx.get_api_config().poll_interval_secs),
+        );
     }
 
-    let filter_state = use_state(|| FilterMode::None);
+    let filter_state = use_state(|| TaskFilter::default());
     let sort_state = use_state(|| SortMode::Created);
+    let all_tags = use_selector(|store: &TaskStore| store.tags());
 
-    tasks = filter_tasks(tasks, *filter_state);
-    tasks = sort_tasks(tasks, *sort_state);
+    // only the visible ids are selected here, so a change to a single task re-renders just its card
+    let task_ids = use_selector_with_deps(
+        |store: &TaskStore, (filter, sort)| {
+            let tasks = sort_tasks(filter.apply(store.tasks()), *sort);
+            tasks.iter().map(|task| task.id).collect::<Vec<i32>>()
+        },
+        ((*filter_state).clone(), *sort_state),
+    );
 
     let token = token.clone();
-    let output = tasks.iter().map(|task|{
+    let output = task_ids.iter().map(|task_id|{
         let token = token.clone();
         let task_dispatch = task_dispatch.clone();
         let remove_onclick = delete_task_callback(
-            task.clone(), task_dispatch.clone(), session_dispatch.clone(), token.clone().unwrap(), || {}, props.error_data.clone());
+            *task_id, task_dispatch.clone(), session_dispatch.clone(), token.clone().unwrap(), || {}, props.error_data.clone());
 
         let toggle_completed = toggle_completed_callback(
-            task.id, task_dispatch.clone(), session_dispatch.clone(), token.clone().unwrap(), props.error_data.clone());
+            *task_id, task_dispatch.clone(), session_dispatch.clone(), token.clone().unwrap(), props.error_data.clone());
 
-        let todo_task = task.clone();
         html! {
-            <Task {todo_task} {remove_onclick} {toggle_completed}/>
+            <Task key={*task_id} task_id={*task_id} {remove_onclick} {toggle_completed}/>
         }
     });
-    let filter_state = filter_state.clone();
 
-    let apply_filter = Callback::from(move |event: Event| {
-        let target_element = event.target_unchecked_into::<HtmlInputElement>();
-        let filter_raw = target_element.value().clone();
-        let filter = match filter_raw.as_str() {
-            "completed" => FilterMode::CompletedTasks,
-            "incompleted" => FilterMode::IncompletedTasks,
-            "priority-a" => FilterMode::PriorityA,
-            "priority-b" => FilterMode::PriorityB,
-            "priority-c" => FilterMode::PriorityC,
-            _ => FilterMode::None,
-        };
-        filter_state.set(filter);
-    });
+    let apply_filter = {
+        let filter_state = filter_state.clone();
+        Callback::from(move |filter: TaskFilter| filter_state.set(filter))
+    };
 
     let sort_state = sort_state.clone();
 
@@ -120,6 +141,7 @@ pub fn tasks(props: &TasksProperties) -> Html {
             "title" => SortMode::Title,
             "priority" => SortMode::Priority,
             "created" => SortMode::Created,
+            "due-date" => SortMode::DueDate,
             _ => SortMode::Created
         };
         sort_state.set(sort);
@@ -133,11 +155,20 @@ pub fn tasks(props: &TasksProperties) -> Html {
         flex-wrap: wrap;
         "#)
         .unwrap();
+    let stale_message = match &*list_status {
+        (true, FetchState::Error, Some(saved_at)) => Some(format!("You are offline, showing tasks saved at {}", format_saved_at(saved_at))),
+        (true, _, Some(saved_at)) => Some(format!("Showing tasks saved at {}, refreshing..", format_saved_at(saved_at))),
+        (true, FetchState::Error, None) => Some("Unable to refresh tasks, they might be out of date".to_string()),
+        _ => None,
+    };
 
     html! {
         <>
+            if let Some(stale_message) = stale_message {
+                <p data-test={"tasks-stale"}>{stale_message}</p>
+            }
             <div class={dropdown_style}>
-                <Dropdown label={"Filter"} options={get_filter_options()} data_test={"filter"} selected_option={get_filter_selected_option()} onchange={apply_filter}/>
+                <FilterBar filter={(*filter_state).clone()} tags={(*all_tags).clone()} onchange={apply_filter}/>
                 <Dropdown label={"Sort"} options={get_sort_options()} data_test={"sort"} selected_option={get_sort_selected_option()} onchange={apply_sort}/>
                 <Button label={"+ add new task"} onclick={new_task} data_test={"add-task"}/>
             </div>
@@ -148,6 +179,13 @@ pub fn tasks(props: &TasksProperties) -> Html {
     }
 }
 
+fn format_saved_at(saved_at: &str) -> String {
+    match DateTime::parse_from_rfc3339(saved_at) {
+        Ok(datetime) => datetime.with_timezone(&Utc).format("%H:%M %d/%m/%y").to_string(),
+        Err(_) => saved_at.to_string(),
+    }
+}
+
 fn sort_tasks(mut tasks: Vec<TodoTask>, sort: SortMode) -> Vec<TodoTask> {
     let sort = get_sort(sort);
     tasks.sort_by(sort);
@@ -180,6 +218,15 @@ fn get_sort(sort: SortMode) -> impl FnMut(&TodoTask, &TodoTask) -> Ordering {
         SortMode::Created => |task_a: &TodoTask, task_b: &TodoTask| {
             task_a.id.cmp(&task_b.id)
         },
+        // the earliest due first, tasks without a due date last
+        SortMode::DueDate => |task_a: &TodoTask, task_b: &TodoTask| {
+            match (task_a.due_at(), task_b.due_at()) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => task_a.id.cmp(&task_b.id),
+            }
+        },
     }
 }
 
@@ -203,88 +250,10 @@ fn get_sort_options() -> Vec<DropdownOption> {
         DropdownOption {
             label: Some("Creation time".to_string()),
             value: "created".to_string(),
-        }
-    ]
-}
-
-fn filter_tasks(tasks: Vec<TodoTask>, filter: FilterMode) -> Vec<TodoTask> {
-    let mut filter = get_filter(filter);
-    tasks.iter().filter_map(|task| filter(task)).collect()
-}
-
-fn get_filter(filter: FilterMode) -> impl FnMut(&TodoTask) -> Option<TodoTask> {
-    match filter {
-        FilterMode::None => move |task: &TodoTask| Some(task.clone()),
-        FilterMode::CompletedTasks => move |task: &TodoTask| match task.completed_at {
-            Some(_) => Some(task.clone()),
-            None => None,
-        },
-        FilterMode::IncompletedTasks => move |task: &TodoTask| match task.completed_at {
-            Some(_) => None,
-            None => Some(task.clone()),
-        },
-        FilterMode::PriorityA => move |task: &TodoTask| match task.priority.clone() {
-            Some(priority) => if priority == Priority::A {
-                    Some(task.clone())
-                }
-                else {
-                    None
-                },
-            None => None
-        },
-        FilterMode::PriorityB => move |task: &TodoTask| match task.priority.clone() {
-            Some(priority) => if priority == Priority::B {
-                    Some(task.clone())
-                }
-                else {
-                    None
-                },
-            None => None
-        },
-        FilterMode::PriorityC => move |task: &TodoTask| match task.priority.clone() {
-            Some(priority) => if priority == Priority::C {
-                    Some(task.clone())
-                }
-                else {
-                    None
-                },
-            None => None
-        },
-    }
-}
-
-fn get_filter_selected_option() -> DropdownOption {
-    DropdownOption {
-        label: Some("None".to_string()),
-        value: "none".to_string(),
-    }
-}
-
-fn get_filter_options() -> Vec<DropdownOption> {
-    vec![
-        DropdownOption {
-            label: Some("None".to_string()),
-            value: "none".to_string(),
-        },
-        DropdownOption {
-            label: Some("Completed tasks".to_string()),
-            value: "completed".to_string(),
-        },
-        DropdownOption {
-            label: Some("Incompleted tasks".to_string()),
-            value: "incompleted".to_string(),
-        },
-        DropdownOption {
-            label: Some("Priority A".to_string()),
-            value: "priority-a".to_string(),
         },
         DropdownOption {
-            label: Some("Priority B".to_string()),
-            value: "priority-b".to_string(),
-        },
-        DropdownOption {
-            label: Some("Priority C".to_string()),
-            value: "priority-c".to_string(),
+            label: Some("Due date".to_string()),
+            value: "due-date".to_string(),
         },
     ]
 }
@@ -306,15 +275,35 @@ fn toggle_completed_callback(
         let tasks_dispatch = tasks_dispatch.clone();
         let session_dispatch = session_dispatch.clone();
         let error_data = error_data.clone();
+        let user_id = session_dispatch.get().user.as_ref().map(|user| user.id);
+        // the store is updated right away and rolled back if the server rejects the change
+        let snapshot = tasks_dispatch.get().get_task(task_id).cloned();
+        let operation = snapshot.as_ref().map(|task| PendingOperation::ToggleCompleted {
+            task_id,
+            completed: !task.completed(),
+        });
+        if let (Some(user_id), Some(operation)) = (user_id, operation.clone()) {
+            if should_queue(user_id) {
+                return queue_operation(user_id, operation);
+            }
+        }
+        if let Some(mut task) = snapshot.clone() {
+            task.toggle_completed();
+            tasks_dispatch.reduce_mut(|store| store.upsert_task(task));
+        }
         spawn_local(async move {
             let response = TasksService::task_toggle_completed(token.clone(), task_id).await;
             match response {
-                Ok(()) => tasks_dispatch.reduce(|store| {
-                    let mut store = store.deref().clone();
-                    store.tasks_valid = false;
-                    store.into()
-                }),
-                Err(error) => handle_api_error(error, &session_dispatch, error_data)
+                Ok(task) => tasks_dispatch.reduce_mut(|store| store.upsert_task(task)),
+                Err(error) => {
+                    if let (Some(user_id), Some(operation), true) = (user_id, operation, is_offline_error(&error)) {
+                        return defer_operation(user_id, operation);
+                    }
+                    if let Some(snapshot) = snapshot {
+                        tasks_dispatch.reduce_mut(|store| store.upsert_task(snapshot));
+                    }
+                    handle_api_error(error, &session_dispatch, error_data)
+                }
             }
         })
     })
@@ -322,36 +311,112 @@ fn toggle_completed_callback(
 
 pub fn update_tasks_in_store(
     token: String,
-    task_store: Rc<TaskStore>,
     task_dispatch: Dispatch<TaskStore>,
     session_dispatch: Dispatch<SessionStore>,
-    error_data: Option<UseStateHandle<ErrorData>>
+    error_data: Option<UseStateHandle<ErrorData>>,
+    abort_signal: Option<AbortSignal>
 ) {
-    let task_store = task_store.clone();
-    let task_dispatch = task_dispatch.clone();
-    let session_dispatch = session_dispatch.clone();
-    let error_data = error_data.clone();
-    if !task_store.clone().tasks_valid {
+    // a fetch that is already in flight is shared instead of starting another one
+    let mut version = None;
+    task_dispatch.reduce_mut(|store| version = store.begin_list_fetch());
+    let Some(version) = version else {
+        return;
+    };
+
+    let user_id = session_dispatch.get().user.as_ref().map(|user| user.id);
+
+    // cached tasks are shown right away while the list is being refreshed
+    if let (false, Some(user_id)) = (task_dispatch.get().has_list(), user_id) {
         let task_dispatch = task_dispatch.clone();
-        let session_dispatch = session_dispatch.clone();
-        return spawn_local(async move {
-            let response = TasksService::get_tasks(token).await;
-            match response {
-                Ok(tasks) => task_dispatch.reduce(|store| {
-                    let mut store = store.deref().clone();
-                    store.tasks = Some(tasks);
-                    store.tasks_valid = true;
-                    store.into()
-                    }
-                ),
-                Err(error) => handle_api_error(error, &session_dispatch, error_data)
+        spawn_local(async move {
+            if let Some(cached) = TaskCache::load(user_id).await {
+                task_dispatch.reduce_mut(|store| {
+                    store.restore_cached(version, cached);
+                    apply_pending(store, user_id);
+                });
             }
         });
     }
+
+    fetch_task_list(version, token, task_dispatch, session_dispatch, error_data, abort_signal);
+}
+
+/// Refreshes the list in the background, even if it's already loaded.
+fn refresh_tasks_in_store(token: String, task_dispatch: Dispatch<TaskStore>, session_dispatch: Dispatch<SessionStore>) {
+    let mut version = None;
+    task_dispatch.reduce_mut(|store| version = store.begin_list_refresh());
+    let Some(version) = version else {
+        return;
+    };
+
+    fetch_task_list(version, token, task_dispatch, session_dispatch, None, None);
+}
+
+fn fetch_task_list(
+    version: u32,
+    token: String,
+    task_dispatch: Dispatch<TaskStore>,
+    session_dispatch: Dispatch<SessionStore>,
+    error_data: Option<UseStateHandle<ErrorData>>,
+    abort_signal: Option<AbortSignal>
+) {
+    // operations still waiting in the outbox are shown on top of whatever the list is loaded from
+    let user_id = session_dispatch.get().user.as_ref().map(|user| user.id);
+
+    spawn_local(async move {
+        // the ETag is only kept for lists fetched from the server, so an unchanged list costs an empty 304
+        let etag = task_dispatch.get().list_etag().cloned();
+        let response = TasksService::get_tasks(token, etag, abort_signal).await;
+        match response {
+            Ok(Conditional::NotModified) => task_dispatch.reduce_mut(|store| store.finish_list_unchanged(version)),
+            Ok(Conditional::Modified { data: tasks, etag }) => task_dispatch.reduce_mut(|store| {
+                store.finish_list_fetch(version, tasks, etag);
+                if let Some(user_id) = user_id {
+                    apply_pending(store, user_id);
+                }
+            }),
+            // the request already retried temporary failures, the next poll tries again
+            Err(error) => {
+                let aborted = ErrorAction::from(&error) == ErrorAction::Ignore;
+                task_dispatch.reduce_mut(|store| store.fail_list_fetch(version, aborted));
+                handle_api_error(error, &session_dispatch, error_data)
+            }
+        }
+    });
+}
+
+/// Refreshes the list every `poll_interval_secs` and whenever the tab becomes visible or the window gains focus.
+/// Nothing is fetched while the tab is hidden or offline.
+fn watch_tasks(
+    token: String,
+    poll_interval_secs: u32,
+    task_dispatch: Dispatch<TaskStore>,
+    session_dispatch: Dispatch<SessionStore>
+) -> (Option<Interval>, EventListener, EventListener) {
+    let refresh = Rc::new(move || {
+        if !document().hidden() && !is_offline() {
+            refresh_tasks_in_store(token.clone(), task_dispatch.clone(), session_dispatch.clone());
+        }
+    });
+
+    let interval = match poll_interval_secs {
+        0 => None,
+        _ => {
+            let refresh = refresh.clone();
+            Some(Interval::new(poll_interval_secs.saturating_mul(1000), move || refresh()))
+        }
+    };
+    let visibility_listener = {
+        let refresh = refresh.clone();
+        EventListener::new(&document(), "visibilitychange", move |_| refresh())
+    };
+    let focus_listener = EventListener::new(&window(), "focus", move |_| refresh());
+
+    (interval, visibility_listener, focus_listener)
 }
 
 pub fn delete_task_callback<F>(
-    task: TodoTask,
+    task_id: i32,
     tasks_dispatch: Dispatch<TaskStore>,
     session_dispatch: Dispatch<SessionStore>,
     token: String,
@@ -365,22 +430,35 @@ where
     let action = action.clone();
     let error_data = error_data.clone();
     Callback::from(move |_: MouseEvent| {
-        let task_id = task.id.clone();
         let tasks_dispatch = tasks_dispatch.clone();
         let session_dispatch = session_dispatch.clone();
         let token = token.clone();
         let action = action.clone();
         let error_data = error_data.clone();
+        let user_id = session_dispatch.get().user.as_ref().map(|user| user.id);
+        let operation = PendingOperation::Delete { task_id };
+        if let Some(user_id) = user_id {
+            if should_queue(user_id) {
+                queue_operation(user_id, operation);
+                return action();
+            }
+        }
+        let mut snapshot = None;
+        tasks_dispatch.reduce_mut(|store| snapshot = store.remove_task(task_id));
         spawn_local(async move {
             let response = TasksService::delete_task(token.clone(), task_id).await;
             match response {
-                Ok(()) => tasks_dispatch.reduce(|store| {
-                    action();
-                    let mut store = store.deref().clone();
-                    store.tasks_valid = false;
-                    store.into()
-                }),
-                Err(error) => handle_api_error(error, &session_dispatch, error_data)
+                Ok(()) => action(),
+                Err(error) => {
+                    if let (Some(user_id), true) = (user_id, is_offline_error(&error)) {
+                        defer_operation(user_id, operation);
+                        return action();
+                    }
+                    if let Some((index, task)) = snapshot {
+                        tasks_dispatch.reduce_mut(|store| store.restore_task(index, task));
+                    }
+                    handle_api_error(error, &session_dispatch, error_data)
+                }
             }
         })
     })
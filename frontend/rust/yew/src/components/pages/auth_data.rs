@@ -1,5 +1,7 @@
 #[derive(Default, PartialEq, Clone, Debug)]
 pub struct AuthData {
     pub username: String,
-    pub password: String
+    pub password: String,
+    /// Keeps the login after the browser is closed, only offered when logging in.
+    pub remember: bool,
 }
@@ -9,6 +9,7 @@ use crate::{
     router::Route,
     styles::{color::Color, styles::Styles},
     SessionStore, TaskStore, utils::handle_api_error, app_context::AppContext,
+    validation::{FormErrors, NEW_PASSWORD_RULES, USERNAME_RULES},
 };
 use lazy_static::__Deref;
 use wasm_bindgen_futures::spawn_local;
@@ -23,19 +24,33 @@ use super::{auth_data::AuthData, error_data::ErrorData};
 pub fn create_account() -> Html {
     let auth_data = use_mut_ref(|| AuthData::default());
     let ctx = use_context::<Rc<AppContext>>().unwrap();
+    let form_errors = use_state(|| FormErrors::new(&[
+        ("username", "", USERNAME_RULES),
+        ("password", "", NEW_PASSWORD_RULES),
+    ]));
 
     let onchange = {
         let auth_data = auth_data.clone();
+        let form_errors = form_errors.clone();
         Callback::from(move |event: Event| {
             let auth_data = auth_data.clone();
             let target_element = event.target_unchecked_into::<HtmlInputElement>();
+            let mut errors = form_errors.deref().clone();
             match target_element.id().as_str() {
-                "username" => auth_data.borrow_mut().username = target_element.value(),
-                "password" => auth_data.borrow_mut().password = target_element.value(),
+                "username" => {
+                    auth_data.borrow_mut().username = target_element.value();
+                    errors.check("username", &target_element.value(), USERNAME_RULES);
+                }
+                "password" => {
+                    auth_data.borrow_mut().password = target_element.value();
+                    errors.check("password", &target_element.value(), NEW_PASSWORD_RULES);
+                }
                 _ => (),
             };
+            form_errors.set(errors);
         })
     };
+    let oninput = onchange.reform(|event: InputEvent| event.into());
 
     let error_data = use_state(|| ErrorData::default());
 
@@ -45,10 +60,15 @@ pub fn create_account() -> Html {
 
     let onsubmit = {
         let error_data = error_data.clone();
+        let form_errors = form_errors.clone();
         Callback::from(move |event: SubmitEvent| {
             event.prevent_default();
+            if !form_errors.is_valid() {
+                return;
+            }
             let auth_data = auth_data.clone();
             let error_data = error_data.clone();
+            let form_errors = form_errors.clone();
             let session_dispatch = session_dispatch.clone();
             let task_dispatch = task_dispatch.clone();
             let history = history.clone();
@@ -70,7 +90,16 @@ pub fn create_account() -> Html {
                         });
                         history.push(&Route::Home)
                     }
-                    Err(error) => handle_api_error(error, &session_dispatch, Some(error_data))
+                    Err(error) => {
+                        // Identity's complaints about the username or password go next to the field
+                        let mut errors = form_errors.deref().clone();
+                        if errors.apply_server_error(&error, &[("username", "user"), ("password", "password")]) {
+                            form_errors.set(errors);
+                        }
+                        else {
+                            handle_api_error(error, &session_dispatch, Some(error_data));
+                        }
+                    }
                 }
             });
         })
@@ -85,10 +114,10 @@ pub fn create_account() -> Html {
         }
         <form class={style} {onsubmit}>
             <h2 class={Color::Secondary.into_style("color", &ctx)}>{"Create account"}</h2>
-            <TextInput id={"username"} onchange={onchange.clone()} label={"Your username"} placeholder={"enter username.."} data_test={"username"}/>
-            <TextInput id={"password"} {onchange} label={"Your password"} input_type={"password"} placeholder={"enter password.."} data_test={"password"}/>
+            <TextInput id={"username"} onchange={onchange.clone()} oninput={oninput.clone()} label={"Your username"} placeholder={"enter username.."} data_test={"username"} error={form_errors.get("username")}/>
+            <TextInput id={"password"} {onchange} {oninput} label={"Your password"} input_type={"password"} placeholder={"enter password.."} data_test={"password"} error={form_errors.get("password")}/>
             <div>
-                <Button label={"Create account!"} data_test={"submit"}/>
+                <Button label={"Create account!"} data_test={"submit"} disabled={!form_errors.is_valid()}/>
             </div>
         </form>
         </>
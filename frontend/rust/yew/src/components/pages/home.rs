@@ -1,7 +1,10 @@
+use std::rc::Rc;
+
 use yew::prelude::*;
 use yewdux::prelude::use_store;
 
 use crate::{
+    app_context::AppContext,
     components::organisms::{error_message::ErrorMessage, tasks::Tasks},
     styles::styles::Styles,
     SessionStore,
@@ -13,6 +16,8 @@ use super::error_data::ErrorData;
 pub fn home() -> Html {
     let error_data = use_state(|| ErrorData::default());
     let (store, _) = use_store::<SessionStore>();
+    let ctx = use_context::<Rc<AppContext>>().unwrap();
+    let api_config = ctx.get_api_config();
 
     let style = Styles::get_home_style();
 
@@ -34,6 +39,9 @@ pub fn home() -> Html {
                 <p>{"Here you could add, delete and modify your tasks, if you were logged in.."}</p>
             </div>
         }
+            <p data-test={"api-uri"} title={format!("Configured by {}", api_config.source)}>
+                {format!("Connected to {}", api_config.api_uri)}
+            </p>
         </div>
         </>
     }
@@ -1,15 +1,17 @@
 use std::rc::Rc;
 
 use crate::{
-    api::auth::auth_service::AuthService,
+    api::{api_config::ApiConfig, auth::auth_service::AuthService},
     components::{
-        atoms::{button::Button, text_input::TextInput},
+        atoms::{button::Button, checkbox::Checkbox, text_input::TextInput},
         organisms::error_message::ErrorMessage,
     },
-    router::Route,
+    router::LoginQuery,
     styles::{color::Color, styles::Styles},
     SessionStore, TaskStore, utils::handle_api_error, app_context::AppContext,
+    validation::{FormErrors, LOGIN_PASSWORD_RULES, USERNAME_RULES},
 };
+use chrono::{Duration, Utc};
 use lazy_static::__Deref;
 use wasm_bindgen_futures::spawn_local;
 use web_sys::HtmlInputElement;
@@ -23,34 +25,55 @@ use super::{auth_data::AuthData, error_data::ErrorData};
 pub fn create_account() -> Html {
     let auth_data = use_mut_ref(|| AuthData::default());
     let ctx = use_context::<Rc<AppContext>>().unwrap();
+    let form_errors = use_state(|| FormErrors::new(&[
+        ("username", "", USERNAME_RULES),
+        ("password", "", LOGIN_PASSWORD_RULES),
+    ]));
 
     let onchange = {
         let auth_data = auth_data.clone();
+        let form_errors = form_errors.clone();
         Callback::from(move |event: Event| {
             let auth_data = auth_data.clone();
             let target_element = event.target_unchecked_into::<HtmlInputElement>();
+            let mut errors = form_errors.deref().clone();
             match target_element.id().as_str() {
-                "username" => auth_data.borrow_mut().username = target_element.value(),
-                "password" => auth_data.borrow_mut().password = target_element.value(),
+                "username" => {
+                    auth_data.borrow_mut().username = target_element.value();
+                    errors.check("username", &target_element.value(), USERNAME_RULES);
+                }
+                "password" => {
+                    auth_data.borrow_mut().password = target_element.value();
+                    errors.check("password", &target_element.value(), LOGIN_PASSWORD_RULES);
+                }
+                "remember" => auth_data.borrow_mut().remember = target_element.checked(),
                 _ => (),
             };
+            form_errors.set(errors);
         })
     };
+    let oninput = onchange.reform(|event: InputEvent| event.into());
 
     let error_data = use_state(|| ErrorData::default());
 
-    let (_, session_dispatch) = use_store::<SessionStore>();
+    let (session_store, session_dispatch) = use_store::<SessionStore>();
     let (_, task_dispatch) = use_store::<TaskStore>();
-    let history = use_navigator().unwrap();
+    let login_required = use_location()
+        .and_then(|location| location.query::<LoginQuery>().ok())
+        .map_or(false, |query| query.next.is_some());
 
     let onsubmit = {
+        let auth_data = auth_data.clone();
         let error_data = error_data.clone();
+        let form_errors = form_errors.clone();
         Callback::from(move |event: SubmitEvent| {
             event.prevent_default();
+            if !form_errors.is_valid() {
+                return;
+            }
             let auth_data = auth_data.clone();
             let session_dispatch = session_dispatch.clone();
             let task_dispatch = task_dispatch.clone();
-            let history = history.clone();
             let error_data = error_data.clone();
             spawn_local(async move {
                 let response = AuthService::login(
@@ -60,15 +83,24 @@ pub fn create_account() -> Html {
                 .await;
                 match response {
                     Ok(auth) => {
+                        let remember_until = match auth_data.borrow().remember {
+                            true => {
+                                let remember_days = ApiConfig::get().remember_days;
+                                Some((Utc::now() + Duration::days(remember_days.into())).to_rfc3339())
+                            }
+                            false => None,
+                        };
+                        task_dispatch.reduce(|_| {
+                            TaskStore::default().into()
+                        });
+                        // the route guard takes the user to the page they asked for
                         session_dispatch.clone().reduce(|store| {
                             let mut store = store.deref().clone();
                             store.user = Some(auth);
+                            store.return_to = None;
+                            store.remember_until = remember_until;
                             store.into()
                         });
-                        task_dispatch.reduce(|_| {
-                            TaskStore::default().into()
-                        });
-                        history.push(&Route::Home)
                     }
                     Err(error) => handle_api_error(error, &session_dispatch, Some(error_data))
                 }
@@ -85,10 +117,17 @@ pub fn create_account() -> Html {
         }
         <form class={style} {onsubmit}>
             <h2 class={Color::Secondary.into_style("color", &ctx)}>{"Login"}</h2>
-            <TextInput id={"username"} onchange={onchange.clone()} label={"Your username"} placeholder={"enter username.."} data_test={"username"}/>
-            <TextInput id={"password"} {onchange} label={"Your password"} input_type={"password"} placeholder={"enter password.."} data_test={"password"}/>
+            if session_store.return_to.is_some() {
+                <p data-test={"session-expired"}>{"Your session has expired, log in again to continue where you left off."}</p>
+            }
+            else if login_required {
+                <p data-test={"login-required"}>{"You must be logged in to view this page."}</p>
+            }
+            <TextInput id={"username"} onchange={onchange.clone()} oninput={oninput.clone()} label={"Your username"} placeholder={"enter username.."} data_test={"username"} error={form_errors.get("username")}/>
+            <TextInput id={"password"} onchange={onchange.clone()} {oninput} label={"Your password"} input_type={"password"} placeholder={"enter password.."} data_test={"password"} error={form_errors.get("password")}/>
+            <Checkbox id={"remember"} {onchange} label={"Remember me"} checked={auth_data.borrow().remember} data_test={"remember-me"}/>
             <div>
-                <Button label={"Log in!"} data_test={"submit"}/>
+                <Button label={"Log in!"} data_test={"submit"} disabled={!form_errors.is_valid()}/>
             </div>
         </form>
         </>
@@ -4,4 +4,5 @@ pub mod login;
 pub mod task_details;
 pub mod new_task;
 pub mod auth_data;
-pub mod error_data;
\ No newline at end of file
+pub mod error_data;
+pub mod not_found;
\ No newline at end of file
@@ -1,21 +1,28 @@
+use std::rc::Rc;
+
 use crate::{
-    api::tasks::{todo_task::TodoTask, tasks_service::TasksService},
+    api::tasks::{recurrence::RecurrenceRule, todo_task::TodoTask, tasks_service::TasksService},
     components::{
         atoms::{
             button::Button,
             checkbox::Checkbox,
+            date_time_picker::DateTimePicker,
             dropdown::Dropdown,
             text_input::{ControlType, TextInput},
         },
+        molecules::{recurrence_editor::RecurrenceEditor, tag_input::TagInput},
         organisms::error_message::ErrorMessage,
         pages::{
             error_data::ErrorData,
             task_details::{get_priority_options, get_selected_value},
         },
     },
+    drafts::{leave_draft, load_draft, remove_draft, save_draft, TaskDraft},
+    outbox::{is_offline_error, queue_create, should_queue},
     router::Route,
     styles::{color::Color, styles::Styles},
     SessionStore, TaskStore, utils::handle_api_error,
+    validation::{FormErrors, TITLE_RULES},
 };
 use lazy_static::__Deref;
 use stylist::yew::styled_component;
@@ -31,28 +38,71 @@ pub fn new_task() -> Html {
 
     let history = use_navigator().unwrap();
     let history = history.clone();
+    let draft_key = Route::NewTask.to_path();
     let goto_home = {
         let history = history.clone();
-        Callback::from(move |_| history.push(&Route::Home))
+        let draft_key = draft_key.clone();
+        Callback::from(move |_| {
+            remove_draft(&draft_key);
+            history.push(&Route::Home)
+        })
     };
 
     let error_data = use_state(|| ErrorData::default());
 
     let (session_store, session_dispatch) = use_store::<SessionStore>();
     let (_, task_dispatch) = use_store::<TaskStore>();
+    let user_id = session_store.user.as_ref().map(|user| user.id);
+
+    // a form left because the session ended is filled in again after logging in
+    let draft = load_draft(&draft_key, user_id);
+    let task_data = use_mut_ref(|| match &draft {
+        Some(draft) => draft.task.clone(),
+        None => TodoTask::default(),
+    });
+    let create_task_as_completed = use_mut_ref(|| draft.as_ref().map_or(false, |draft| draft.as_completed));
+    let form_errors = use_state(|| FormErrors::new(&[("title", &task_data.borrow().title, TITLE_RULES)]));
+
+    {
+        let draft_key = draft_key.clone();
+        use_effect_with_deps(move |_| move || leave_draft(&draft_key), ());
+    }
 
-    let task_data = use_mut_ref(|| TodoTask::default());
-    let create_task_as_completed = use_mut_ref(|| false);
+    let suggested_tags = use_selector(|store: &TaskStore| store.tags());
+    let force_update = use_force_update();
+
+    let save_task_draft = {
+        let task_data = task_data.clone();
+        let create_task_as_completed = create_task_as_completed.clone();
+        let draft_key = draft_key.clone();
+        Rc::new(move || {
+            if let Some(user_id) = user_id {
+                save_draft(&draft_key, TaskDraft {
+                    user_id,
+                    task: task_data.borrow().clone(),
+                    base: None,
+                    as_completed: *create_task_as_completed.borrow(),
+                });
+            }
+        })
+    };
 
     let task_dispatch = task_dispatch.clone();
     let onchange = {
         let task_data = task_data.clone();
         let create_task_as_completed = create_task_as_completed.clone();
+        let save_task_draft = save_task_draft.clone();
+        let form_errors = form_errors.clone();
         Callback::from(move |event: Event| {
             let target_element = event.target_unchecked_into::<HtmlInputElement>();
             let value = target_element.value();
             match target_element.id().as_str() {
-                "title" => task_data.borrow_mut().title = value.clone(),
+                "title" => {
+                    task_data.borrow_mut().title = value.clone();
+                    let mut errors = form_errors.deref().clone();
+                    errors.check("title", &value, TITLE_RULES);
+                    form_errors.set(errors);
+                }
                 "priority" => {
                     task_data.borrow_mut().priority = match value.parse() {
                         Ok(priority) => Some(priority),
@@ -71,6 +121,49 @@ pub fn new_task() -> Html {
                 }
                 _ => (),
             };
+            save_task_draft();
+        })
+    };
+    let oninput = onchange.reform(|event: InputEvent| event.into());
+
+    let on_due_change = {
+        let task_data = task_data.clone();
+        let save_task_draft = save_task_draft.clone();
+        Callback::from(move |due_at| {
+            task_data.borrow_mut().set_due_at(due_at);
+            save_task_draft();
+        })
+    };
+
+    let on_recurrence_change = {
+        let task_data = task_data.clone();
+        let save_task_draft = save_task_draft.clone();
+        let force_update = force_update.clone();
+        Callback::from(move |recurrence: Option<RecurrenceRule>| {
+            task_data.borrow_mut().recurrence = recurrence;
+            save_task_draft();
+            force_update.force_update();
+        })
+    };
+
+    let on_add_tag = {
+        let task_data = task_data.clone();
+        let save_task_draft = save_task_draft.clone();
+        let force_update = force_update.clone();
+        Callback::from(move |tag: String| {
+            task_data.borrow_mut().add_tag(&tag);
+            save_task_draft();
+            force_update.force_update();
+        })
+    };
+
+    let on_remove_tag = {
+        let task_data = task_data.clone();
+        let save_task_draft = save_task_draft.clone();
+        Callback::from(move |tag: String| {
+            task_data.borrow_mut().remove_tag(&tag);
+            save_task_draft();
+            force_update.force_update();
         })
     };
 
@@ -83,9 +176,14 @@ pub fn new_task() -> Html {
         };
         let task_data = task_data.clone();
         let create_task_as_completed = create_task_as_completed.clone();
+        let draft_key = draft_key.clone();
         let task_dispatch = task_dispatch.clone();
         let session_dispatch = session_dispatch.clone();
+        let form_errors = form_errors.clone();
         Callback::from(move |_: MouseEvent| {
+            if !form_errors.is_valid() {
+                return;
+            }
             let history = history.clone();
             let task_dispatch = task_dispatch.clone();
             let session_dispatch = session_dispatch.clone();
@@ -93,24 +191,47 @@ pub fn new_task() -> Html {
             let token = token.clone();
             let task: TodoTask = task_data.deref().clone().into();
             let error_data = error_data.clone();
+            let draft_key = draft_key.clone();
+            let form_errors = form_errors.clone();
 
             if let None = token {
                 return;
             }
 
+            let as_completed = *create_task_as_completed.borrow();
+            if let Some(user_id) = user_id {
+                if should_queue(user_id) {
+                    queue_create(user_id, task, as_completed);
+                    remove_draft(&draft_key);
+                    return history.push(&Route::Home);
+                }
+            }
+
             spawn_local(async move {
                 let response =
-                    TasksService::create_task(token.clone().unwrap(), task.clone(), *create_task_as_completed.borrow()).await;
+                    TasksService::create_task(token.clone().unwrap(), task.clone(), as_completed).await;
                 match response {
                     Ok(_) => {
+                        remove_draft(&draft_key);
                         history.push(&Route::Home);
-                        task_dispatch.reduce(|store| {
-                            let mut store = store.deref().clone();
-                            store.tasks_valid = false;
-                            store.into()
-                        })
+                        task_dispatch.reduce_mut(|store| store.invalidate_list())
+                    }
+                    Err(error) => match (user_id, is_offline_error(&error)) {
+                        (Some(user_id), true) => {
+                            queue_create(user_id, task, as_completed);
+                            remove_draft(&draft_key);
+                            history.push(&Route::Home)
+                        }
+                        _ => {
+                            let mut errors = form_errors.deref().clone();
+                            if errors.apply_server_error(&error, &[("title", "title")]) {
+                                form_errors.set(errors);
+                            }
+                            else {
+                                handle_api_error(error, &session_dispatch, Some(error_data));
+                            }
+                        }
                     }
-                    Err(error) => handle_api_error(error, &session_dispatch, Some(error_data))
                 }
             })
         })
@@ -123,10 +244,13 @@ pub fn new_task() -> Html {
         }
         <div class={style}>
             <h3>{"Create new task!"}</h3>
-            <TextInput data_test={"title"} id={"title"} label={"Title"} onchange={onchange.clone()}/>
-            <Dropdown data_test={"priority"} id={"priority"} label={"Priority"} options={get_priority_options()} selected_option={get_selected_value(None)} onchange={onchange.clone()}/>
-            <TextInput data_test={"description"} id={"description"} label={"Description"} control_type={ControlType::Textarea} rows={3} onchange={onchange.clone()}/>
-            <Checkbox data_test={"completed"} id={"completed"} label={"Completed?"} checked={task_data.borrow().completed()} onchange={onchange.clone()}/>
+            <TextInput data_test={"title"} id={"title"} label={"Title"} text={task_data.borrow().title.clone()} onchange={onchange.clone()} {oninput} error={form_errors.get("title")}/>
+            <Dropdown data_test={"priority"} id={"priority"} label={"Priority"} options={get_priority_options()} selected_option={get_selected_value(task_data.borrow().priority.clone())} onchange={onchange.clone()}/>
+            <DateTimePicker data_test={"due-at"} id={"due-at"} label={"Due date"} value={task_data.borrow().due_at()} onchange={on_due_change}/>
+            <RecurrenceEditor data_test={"recurrence"} id={"recurrence"} recurrence={task_data.borrow().recurrence.clone()} onchange={on_recurrence_change}/>
+            <TagInput data_test={"tags"} id={"tags"} label={"Tags"} tags={task_data.borrow().tags.clone()} suggestions={(*suggested_tags).clone()} onadd={on_add_tag} onremove={on_remove_tag}/>
+            <TextInput data_test={"description"} id={"description"} label={"Description"} control_type={ControlType::Textarea} rows={3} text={task_data.borrow().description.clone()} onchange={onchange.clone()}/>
+            <Checkbox data_test={"completed"} id={"completed"} label={"Completed?"} checked={*create_task_as_completed.borrow()} onchange={onchange.clone()}/>
             <div class={button_style}>
                 <Button
                     label={"Cancel"}
@@ -135,7 +259,7 @@ pub fn new_task() -> Html {
                     hover_color={Color::Error2}
                     data_test={"cancel"}
                     onclick={goto_home.clone()}/>
-                <Button label={"Create task"} onclick={create_task.clone()} data_test={"submit"}/>
+                <Button label={"Create task"} onclick={create_task.clone()} data_test={"submit"} disabled={!form_errors.is_valid()}/>
             </div>
         </div>
         </>
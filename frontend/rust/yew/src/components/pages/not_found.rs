@@ -0,0 +1,21 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::{components::atoms::button::Button, router::Route, styles::styles::Styles};
+
+#[function_component(NotFound)]
+pub fn not_found() -> Html {
+    let (style, button_style) = Styles::get_editable_details_style();
+    let history = use_navigator().unwrap();
+    let goto_home = Callback::from(move |_| history.push(&Route::Home));
+
+    html! {
+        <div class={style}>
+            <h3 data-test={"not-found"}>{"Page not found"}</h3>
+            <p>{"There is nothing at this address, it might have been mistyped or the page no longer exists."}</p>
+            <div class={button_style}>
+                <Button label={"Return to tasks"} onclick={goto_home} data_test={"goto-home"}/>
+            </div>
+        </div>
+    }
+}
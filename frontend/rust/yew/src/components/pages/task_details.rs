@@ -1,37 +1,78 @@
 use std::rc::Rc;
-use chrono::{Utc, SecondsFormat};
+
+use chrono::{Local, Utc, SecondsFormat};
 use lazy_static::__Deref;
 use wasm_bindgen_futures::spawn_local;
-use web_sys::HtmlInputElement;
+use web_sys::{AbortController, HtmlInputElement};
 use yew::prelude::*;
 use yew_router::prelude::*;
-use yewdux::prelude::use_store;
+use yewdux::prelude::*;
 
 use crate::{
-    api::tasks::{
-        todo_task::{Priority, TodoTask},
-        tasks_service::TasksService,
+    api::{
+        api_client::{ApiError, NOT_OWNED_ERROR_CODE},
+        tasks::{
+            recurrence::RecurrenceRule,
+            task_merge::{TaskField, TaskMerge},
+            todo_task::{Priority, Subtask, TodoTask},
+            tasks_service::TasksService,
+        },
     },
     components::{
         atoms::{
             button::Button,
             checkbox::Checkbox,
+            date_time_picker::DateTimePicker,
             dropdown::{Dropdown, DropdownOption},
             text_display::TextDisplay,
             text_input::{ControlType, TextInput},
         },
+        molecules::{
+            recurrence_editor::RecurrenceEditor,
+            subtasks::{SubtaskChecklist, SubtaskEditor},
+            tag_input::TagInput,
+            tag_list::TagList,
+        },
         organisms::{
             error_message::ErrorMessage,
-            tasks::{delete_task_callback, update_tasks_in_store},
+            task_merge_view::TaskMergeView,
+            tasks::delete_task_callback,
         },
     },
+    drafts::{leave_draft, load_draft, remove_draft, save_draft, TaskDraft},
+    outbox::{defer_operation, is_offline_error, queue_operation, should_queue, OutboxStore, PendingOperation},
     router::Route,
     styles::{color::Color, styles::Styles},
-    SessionStore, TaskStore, utils::handle_api_error,
+    SessionStore, TaskStore, task_store::FetchState, utils::{handle_api_error, ErrorAction},
+    validation::{FormErrors, TITLE_RULES},
 };
 
 use super::error_data::ErrorData;
 
+#[derive(PartialEq, Clone, Debug)]
+enum TaskState {
+    Loading,
+    Loaded(TodoTask),
+    NotFound,
+    Forbidden,
+    Unauthenticated,
+    Failed,
+}
+
+impl TaskState {
+    /// Maps a failed fetch to the state shown on the page, `None` if the request was cancelled.
+    fn from_error(error: &ApiError) -> Option<Self> {
+        match (error.status(), ErrorAction::from(error)) {
+            (_, ErrorAction::Ignore) => None,
+            (Some(404), _) => Some(TaskState::NotFound),
+            // any other 403 means the token is no longer valid
+            (Some(403), _) if error.code() == Some(NOT_OWNED_ERROR_CODE) => Some(TaskState::Forbidden),
+            (_, ErrorAction::EndSession) => Some(TaskState::Unauthenticated),
+            _ => Some(TaskState::Failed),
+        }
+    }
+}
+
 #[derive(Properties, PartialEq)]
 pub struct TaskDetailsProperties {
     pub task_id: i32,
@@ -44,17 +85,78 @@ pub fn task_details(props: &TaskDetailsProperties) -> Html {
     let error_data = use_state(|| ErrorData::default());
 
     let (session_store, session_dispatch) = use_store::<SessionStore>();
-    let (task_store, task_dispatch) = use_store::<TaskStore>();
+    let task_dispatch = Dispatch::<TaskStore>::new();
+    let stored_task = use_selector_with_deps(
+        |store: &TaskStore, id| (store.get_task(*id).cloned(), store.task_state(*id)),
+        props.task_id,
+    );
 
-    let task_data = use_mut_ref(|| TodoTask::default());
+    // an edit left because the session ended is picked up again after logging in
+    let draft_key = Route::TaskDetails { id: props.task_id }.to_path();
+    let user_id = session_store.user.as_ref().map(|user| user.id);
+    let draft = load_draft(&draft_key, user_id);
+    let task_data = use_mut_ref(|| match &draft {
+        Some(draft) => draft.task.clone(),
+        None => TodoTask::default(),
+    });
+    let edit_state = use_state(|| draft.is_some());
+    let edit_base = use_mut_ref(|| draft.as_ref().and_then(|draft| draft.base.clone()));
+    let form_errors = use_state(|| match &draft {
+        Some(draft) => FormErrors::new(&[("title", &draft.task.title, TITLE_RULES)]),
+        None => FormErrors::default(),
+    });
+
+    {
+        let draft_key = draft_key.clone();
+        use_effect_with_deps(move |_| move || leave_draft(&draft_key), draft_key.clone());
+    }
+    {
+        // the draft is done with once the edit is saved or discarded
+        let draft_key = draft_key.clone();
+        use_effect_with_deps(
+            move |editing| {
+                if !*editing {
+                    remove_draft(&draft_key);
+                }
+                || ()
+            },
+            *edit_state,
+        );
+    }
+
+    let suggested_tags = use_selector(|store: &TaskStore| store.tags());
+    let force_update = use_force_update();
+
+    let save_task_draft = {
+        let task_data = task_data.clone();
+        let edit_base = edit_base.clone();
+        let draft_key = draft_key.clone();
+        Rc::new(move || {
+            if let Some(user_id) = user_id {
+                save_draft(&draft_key, TaskDraft {
+                    user_id,
+                    task: task_data.borrow().clone(),
+                    base: edit_base.borrow().clone(),
+                    as_completed: false,
+                });
+            }
+        })
+    };
 
     let onchange = {
         let task_data = task_data.clone();
+        let save_task_draft = save_task_draft.clone();
+        let form_errors = form_errors.clone();
         Callback::from(move |event: Event| {
             let target_element = event.target_unchecked_into::<HtmlInputElement>();
             let value = target_element.value();
             match target_element.id().as_str() {
-                "title" => task_data.borrow_mut().title = value.clone(),
+                "title" => {
+                    task_data.borrow_mut().title = value.clone();
+                    let mut errors = form_errors.deref().clone();
+                    errors.check("title", &value, TITLE_RULES);
+                    form_errors.set(errors);
+                }
                 "priority" => task_data.borrow_mut().priority = match value.parse() {
                         Ok(priority) => Some(priority),
                         Err(_) => None
@@ -75,47 +177,261 @@ pub fn task_details(props: &TaskDetailsProperties) -> Html {
                     },
                 _ => (),
             };
+            save_task_draft();
+        })
+    };
+    let oninput = onchange.reform(|event: InputEvent| event.into());
+
+    let on_due_change = {
+        let task_data = task_data.clone();
+        let save_task_draft = save_task_draft.clone();
+        Callback::from(move |due_at| {
+            task_data.borrow_mut().set_due_at(due_at);
+            save_task_draft();
+        })
+    };
+
+    let on_recurrence_change = {
+        let task_data = task_data.clone();
+        let save_task_draft = save_task_draft.clone();
+        let force_update = force_update.clone();
+        Callback::from(move |recurrence: Option<RecurrenceRule>| {
+            task_data.borrow_mut().recurrence = recurrence;
+            save_task_draft();
+            force_update.force_update();
+        })
+    };
+
+    let on_add_tag = {
+        let task_data = task_data.clone();
+        let save_task_draft = save_task_draft.clone();
+        let force_update = force_update.clone();
+        Callback::from(move |tag: String| {
+            task_data.borrow_mut().add_tag(&tag);
+            save_task_draft();
+            force_update.force_update();
+        })
+    };
+
+    let on_subtasks_change = {
+        let task_data = task_data.clone();
+        let save_task_draft = save_task_draft.clone();
+        let force_update = force_update.clone();
+        Callback::from(move |subtasks: Vec<Subtask>| {
+            task_data.borrow_mut().subtasks = subtasks;
+            save_task_draft();
+            force_update.force_update();
+        })
+    };
+
+    let on_remove_tag = {
+        let task_data = task_data.clone();
+        let save_task_draft = save_task_draft.clone();
+        Callback::from(move |tag: String| {
+            task_data.borrow_mut().remove_tag(&tag);
+            save_task_draft();
+            force_update.force_update();
         })
     };
 
     let history = use_navigator().unwrap();
 
+    let task_state = use_state(|| match session_store.user {
+        Some(_) => TaskState::Loading,
+        None => TaskState::Unauthenticated,
+    });
+    let reload = use_state(|| 0u32);
+
     {
-        let task_store = task_store.clone();
+        let task_state = task_state.clone();
         let task_dispatch = task_dispatch.clone();
         let session_dispatch = session_dispatch.clone();
-        if let Some(user) = session_store.user.clone() {
-            update_tasks_in_store(user.token, task_store, task_dispatch, session_dispatch, Some(error_data.clone()));
-        }
+        let error_data = error_data.clone();
+        let token = session_store.user.clone().map(|user| user.token);
+        use_effect_with_deps(
+            move |(token, task_id, _)| {
+                // cancels the pending fetch when leaving the page or switching to another task
+                let controller = AbortController::new().ok();
+                match token.clone() {
+                    Some(token) => {
+                        if !matches!(&*task_state, TaskState::Loaded(task) if task.id == *task_id) {
+                            task_state.set(TaskState::Loading);
+                        }
+                        let task_id = *task_id;
+                        // tasks created offline only exist locally until the outbox is replayed
+                        if task_id < 0 && task_dispatch.get().get_task(task_id).is_none() {
+                            task_state.set(TaskState::NotFound);
+                        }
+                        let abort_signal = controller.as_ref().map(|controller| controller.signal());
+                        // a fetch of the same task that is already in flight will update the store for us
+                        let mut start = false;
+                        if task_id > 0 {
+                            task_dispatch.reduce_mut(|store| start = store.begin_task_fetch(task_id));
+                        }
+                        if start {
+                            spawn_local(async move {
+                                match TasksService::get_task(token, task_id, abort_signal).await {
+                                    Ok(task) => {
+                                        task_dispatch.reduce_mut(|store| store.finish_task_fetch(task.clone()));
+                                        task_state.set(TaskState::Loaded(task));
+                                    }
+                                    Err(error) => {
+                                        let state = TaskState::from_error(&error);
+                                        task_dispatch.reduce_mut(|store| match &state {
+                                            Some(TaskState::NotFound) => store.finish_task_missing(task_id, FetchState::NotFound),
+                                            Some(TaskState::Forbidden) => store.finish_task_missing(task_id, FetchState::Forbidden),
+                                            _ => store.fail_task_fetch(task_id, state.is_none()),
+                                        });
+                                        if let Some(state) = state.clone() {
+                                            task_state.set(state);
+                                        }
+                                        if !matches!(state, Some(TaskState::NotFound) | Some(TaskState::Forbidden)) {
+                                            handle_api_error(error, &session_dispatch, Some(error_data));
+                                        }
+                                    }
+                                }
+                            });
+                        }
+                    }
+                    None => task_state.set(TaskState::Unauthenticated),
+                }
+                move || {
+                    if let Some(controller) = controller {
+                        controller.abort();
+                    }
+                }
+            },
+            (token, props.task_id, *reload),
+        );
+    }
+    {
+        // a fetch this page waited on was cancelled by the page that started it, so it's started again
+        let task_state = task_state.clone();
+        let task_dispatch = task_dispatch.clone();
+        let reload = reload.clone();
+        use_effect_with_deps(
+            move |(task_id, _)| {
+                let store = task_dispatch.get();
+                let abandoned = *task_id > 0
+                    && store.get_task(*task_id).is_none()
+                    && store.task_state(*task_id) == FetchState::Idle;
+                if abandoned && *task_state == TaskState::Loading {
+                    reload.set(*reload + 1);
+                }
+                || ()
+            },
+            (props.task_id, stored_task.1),
+        );
     }
 
-    let edit_state = use_state(|| false);
-    let task = match *edit_state {
-        true => Some(task_data.deref().clone().into()),
-        false => get_task_by_id(props.task_id, task_store.clone()),
+    let merge_state = use_state(|| None::<TaskMerge>);
+    {
+        // an edit queued while offline that conflicted on replay is resolved here
+        let merge_state = merge_state.clone();
+        let queued_merge = use_selector_with_deps(
+            |outbox: &OutboxStore, (user_id, id): &(Option<u32>, i32)| {
+                user_id.and_then(|user_id| outbox.merge(user_id, *id).cloned())
+            },
+            (user_id, props.task_id),
+        );
+        use_effect_with_deps(
+            move |queued_merge| {
+                if let Some(merge) = &**queued_merge {
+                    if merge_state.is_none() {
+                        merge_state.set(Some(merge.clone()));
+                    }
+                }
+                || ()
+            },
+            queued_merge,
+        );
+    }
+
+    let goto_home = {
+        let history = history.clone();
+        Callback::from(move |_| history.push(&Route::Home))
     };
 
-    if let None = task {
-        return html! {
-            <ErrorMessage message={"You must be logged in to view tasks"} data_test={"error"}/>
-        };
-    }
+    // the store has the latest version of the task, e.g. after an edit or a fetch started elsewhere
+    let state = match (&*task_state, stored_task.as_ref()) {
+        // the session can end while the page is open
+        _ if session_store.user.is_none() => TaskState::Unauthenticated,
+        (TaskState::Loading | TaskState::Loaded(_) | TaskState::Failed, (Some(task), _)) => TaskState::Loaded(task.clone()),
+        (TaskState::Loading, (None, FetchState::Error)) => TaskState::Failed,
+        (TaskState::Loading, (None, FetchState::NotFound)) => TaskState::NotFound,
+        (TaskState::Loading, (None, FetchState::Forbidden)) => TaskState::Forbidden,
+        (state, _) => state.clone(),
+    };
+
+    let loaded_task = match &state {
+        TaskState::Loaded(task) => task.clone(),
+        TaskState::Unauthenticated => {
+            return html! {
+                <ErrorMessage message={"You must be logged in to view tasks"} data_test={"error"}/>
+            };
+        }
+        state => {
+            let retry = {
+                let reload = reload.clone();
+                Callback::from(move |_: MouseEvent| reload.set(*reload + 1))
+            };
+            let (title, message) = match state {
+                TaskState::Loading => ("Loading task..", "Please wait while the task is being fetched."),
+                TaskState::NotFound => ("Task not found", "This task doesn't exist, it might have been deleted."),
+                TaskState::Forbidden => ("Access denied", "This task belongs to another user."),
+                _ => ("Task unavailable", "The task couldn't be loaded right now."),
+            };
+            return html! {
+                <>
+                if error_data.display {
+                    <ErrorMessage message={error_data.message.clone()}/>
+                }
+                <div class={style}>
+                    <h3 data-test={"task-state"}>{title}</h3>
+                    <p>{message}</p>
+                    <div class={button_style}>
+                    if *state == TaskState::Failed {
+                        <Button data_test={"retry"} label={"Try again"} onclick={retry}/>
+                    }
+                        <Button label={"Return to tasks"} onclick={goto_home}/>
+                    </div>
+                </div>
+                </>
+            };
+        }
+    };
 
-    let task = task.unwrap();
+    let task = match *edit_state {
+        true => task_data.deref().clone().into(),
+        false => loaded_task.clone(),
+    };
 
     let toggle_edit = {
-        let task_store = task_store.clone();
-        let task_id = props.clone().task_id;
+        let loaded_task = loaded_task.clone();
         let edit_state = edit_state.clone();
+        let edit_base = edit_base.clone();
+        let merge_state = merge_state.clone();
         let task_data = task_data.clone();
+        let form_errors = form_errors.clone();
         Callback::from(move |_: MouseEvent| {
-            let task = get_task_by_id(task_id.clone(), task_store.clone()).unwrap();
+            let task = loaded_task.clone();
+            form_errors.set(FormErrors::new(&[("title", &task.title, TITLE_RULES)]));
+            // kept to tell our changes apart from those made elsewhere in the meantime
+            *edit_base.borrow_mut() = match *edit_state {
+                true => None,
+                false => Some(task.clone()),
+            };
+            merge_state.set(None);
             let task_data = task_data.clone();
             task_data.borrow_mut().id = task.id.clone();
             task_data.borrow_mut().title = task.title.clone();
             task_data.borrow_mut().priority = task.priority.clone();
             task_data.borrow_mut().description = task.description.clone();
             task_data.borrow_mut().completed_at = task.completed_at.clone();
+            task_data.borrow_mut().due_at = task.due_at.clone();
+            task_data.borrow_mut().tags = task.tags.clone();
+            task_data.borrow_mut().subtasks = task.subtasks.clone();
+            task_data.borrow_mut().recurrence = task.recurrence.clone();
             let edit_state = edit_state.clone();
             edit_state.set(!*edit_state);
         })
@@ -123,59 +439,180 @@ pub fn task_details(props: &TaskDetailsProperties) -> Html {
 
     let save_changes = {
         let error_data = error_data.clone();
-        let history = history.clone();
         let edit_state = edit_state.clone();
+        let edit_base = edit_base.clone();
+        let merge_state = merge_state.clone();
         let task_data = task_data.clone();
-        let session_task = get_task_by_id(task_data.borrow().id, task_store.clone()).unwrap_or_default();
         let token = session_store.user.clone().unwrap().token;
 
-        if task_data.borrow().completed() && session_task.completed() {
-            task_data.borrow_mut().completed_at = session_task.completed_at.clone();
+        if task_data.borrow().completed() && loaded_task.completed() {
+            task_data.borrow_mut().completed_at = loaded_task.completed_at.clone();
         }
 
+        let loaded_task = loaded_task.clone();
         let task_dispatch = task_dispatch.clone();
         let session_dispatch = session_dispatch.clone();
+        let form_errors = form_errors.clone();
         Callback::from(move |_: MouseEvent| {
+            if !form_errors.is_valid() {
+                return;
+            }
             let error_data = error_data.clone();
-            let history = history.clone();
             let edit_state = edit_state.clone();
+            let merge_state = merge_state.clone();
             let task_dispatch = task_dispatch.clone();
             let session_dispatch = session_dispatch.clone();
             let token = token.clone();
             let task: TodoTask = task_data.deref().clone().into();
+            let snapshot = loaded_task.clone();
+            let base = edit_base.borrow().clone().unwrap_or_else(|| loaded_task.clone());
+            let user_id = session_dispatch.get().user.as_ref().map(|user| user.id);
+            edit_state.set(false);
+            if let Some(user_id) = user_id {
+                if should_queue(user_id) {
+                    return queue_operation(user_id, PendingOperation::Update { task, base: Some(base) });
+                }
+            }
+            // the edit is shown right away, changes made elsewhere since editing started
+            // are merged in once the server tells us about them, or shown as conflicts
+            task_dispatch.reduce_mut(|store| store.upsert_task(task.clone()));
             spawn_local(async move {
-                let response = TasksService::update_task(token.clone(), task.clone()).await;
-                match response {
-                    Ok(()) => {
-                        history.push(&Route::TaskDetails {
-                            id: task.id,
-                        });
-                        task_dispatch.reduce(|store| {
-                            edit_state.set(false);
-                            let mut store = store.deref().clone();
-                            store.tasks_valid = false;
-                            store.into()
-                        })
+                match TasksService::get_task(token.clone(), task.id, None).await {
+                    Ok(remote) => {
+                        let merge = TaskMerge::new(base, task, remote);
+                        if merge.has_conflicts() {
+                            return merge_state.set(Some(merge));
+                        }
+                        update_task_optimistically(
+                            token, merge.merged, merge.remote, user_id, task_dispatch, session_dispatch, error_data);
                     }
-                    Err(error) => handle_api_error(error, &session_dispatch, Some(error_data))
+                    Err(error) => match (user_id, is_offline_error(&error)) {
+                        (Some(user_id), true) => {
+                            defer_operation(user_id, PendingOperation::Update { task, base: Some(base) })
+                        }
+                        _ => {
+                            task_dispatch.reduce_mut(|store| store.upsert_task(snapshot));
+                            handle_api_error(error, &session_dispatch, Some(error_data))
+                        }
+                    },
                 }
             })
         })
     };
 
-    let history = history.clone();
-    let goto_home = {
-        let history = history.clone();
-        Callback::from(move |_| history.push(&Route::Home))
+    let resolve_conflict = {
+        let merge_state = merge_state.clone();
+        Callback::from(move |(field, keep_local): (TaskField, bool)| {
+            if let Some(mut merge) = (*merge_state).clone() {
+                merge.resolve(field, keep_local);
+                merge_state.set(Some(merge));
+            }
+        })
+    };
+
+    let save_merged = {
+        let error_data = error_data.clone();
+        let edit_state = edit_state.clone();
+        let merge_state = merge_state.clone();
+        let task_dispatch = task_dispatch.clone();
+        let session_dispatch = session_dispatch.clone();
+        let token = session_store.user.clone().unwrap().token;
+        Callback::from(move |_: MouseEvent| {
+            if let Some(merge) = (*merge_state).clone() {
+                let user_id = session_dispatch.get().user.as_ref().map(|user| user.id);
+                forget_queued_merge(user_id, merge.local.id);
+                merge_state.set(None);
+                edit_state.set(false);
+                update_task_optimistically(
+                    token.clone(),
+                    merge.merged,
+                    merge.remote,
+                    user_id,
+                    task_dispatch.clone(),
+                    session_dispatch.clone(),
+                    error_data.clone(),
+                );
+            }
+        })
+    };
+
+    let discard_merge = {
+        let edit_state = edit_state.clone();
+        let merge_state = merge_state.clone();
+        let task_dispatch = task_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(merge) = (*merge_state).clone() {
+                forget_queued_merge(user_id, merge.local.id);
+                task_dispatch.reduce_mut(|store| store.upsert_task(merge.remote));
+            }
+            merge_state.set(None);
+            edit_state.set(false);
+        })
+    };
+
+    // checking items off doesn't need the edit form, the task is saved right away
+    let update_task = {
+        let loaded_task = loaded_task.clone();
+        let task_dispatch = task_dispatch.clone();
+        let session_dispatch = session_dispatch.clone();
+        let error_data = error_data.clone();
+        let token = session_store.user.clone().unwrap().token;
+        Callback::from(move |task: TodoTask| {
+            let user_id = session_dispatch.get().user.as_ref().map(|user| user.id);
+            if let Some(user_id) = user_id {
+                if should_queue(user_id) {
+                    let base = Some(loaded_task.clone());
+                    return queue_operation(user_id, PendingOperation::Update { task, base });
+                }
+            }
+            update_task_optimistically(
+                token.clone(),
+                task,
+                loaded_task.clone(),
+                user_id,
+                task_dispatch.clone(),
+                session_dispatch.clone(),
+                error_data.clone(),
+            );
+        })
+    };
+
+    let toggle_subtask = {
+        let loaded_task = loaded_task.clone();
+        let update_task = update_task.clone();
+        Callback::from(move |index: usize| {
+            let mut task = loaded_task.clone();
+            if let Some(subtask) = task.subtasks.get_mut(index) {
+                subtask.completed = !subtask.completed;
+                update_task.emit(task);
+            }
+        })
+    };
+
+    let complete_all_subtasks = {
+        let loaded_task = loaded_task.clone();
+        let task_dispatch = task_dispatch.clone();
+        let session_dispatch = session_dispatch.clone();
+        let error_data = error_data.clone();
+        let token = session_store.user.clone().unwrap().token;
+        Callback::from(move |_: MouseEvent| {
+            complete_all_subtasks_optimistically(
+                token.clone(),
+                loaded_task.clone(),
+                session_dispatch.get().user.as_ref().map(|user| user.id),
+                task_dispatch.clone(),
+                session_dispatch.clone(),
+                error_data.clone(),
+            );
+        })
     };
 
     let delete_task = {
-        let task = task.clone();
         let history = history.clone();
         let task_dispatch = task_dispatch.clone();
         let session_store = session_store.clone();
         delete_task_callback(
-            task.clone(),
+            task.id,
             task_dispatch.clone(),
             session_dispatch.clone(),
             session_store.user.clone().unwrap().token.clone(),
@@ -184,9 +621,7 @@ pub fn task_details(props: &TaskDetailsProperties) -> Html {
         )
     };
 
-    let session_title = get_task_by_id(props.task_id, task_store.clone())
-        .unwrap_or_default()
-        .title;
+    let session_title = loaded_task.title.clone();
 
     html! {
         <>
@@ -196,11 +631,18 @@ pub fn task_details(props: &TaskDetailsProperties) -> Html {
         <div class={style}>
             <h3>{session_title.clone()}</h3>
             <p>{"Here you can view and edit task details."}</p>
-            if *edit_state {
+            if let Some(merge) = (*merge_state).clone() {
+                <TaskMergeView {merge} on_resolve={resolve_conflict} on_save={save_merged} on_discard={discard_merge}/>
+            }
+            else if *edit_state {
                 <TextDisplay id={"id"} label={"ID"} text={task.id.to_string()}/>
-                <TextInput data_test={"editing-title"} id={"title"} label={"Title"} text={task.title.clone()} onchange={onchange.clone()}/>
+                <TextInput data_test={"editing-title"} id={"title"} label={"Title"} text={task.title.clone()} onchange={onchange.clone()} {oninput} error={form_errors.get("title")}/>
                 <Dropdown data_test={"editing-priority"} id={"priority"} label={"Priority"} options={get_priority_options()} selected_option={get_selected_value(task.priority)} onchange={onchange.clone()}/>
+                <DateTimePicker data_test={"editing-due-at"} id={"due-at"} label={"Due date"} value={task.due_at()} onchange={on_due_change}/>
+                <RecurrenceEditor data_test={"editing-recurrence"} id={"recurrence"} recurrence={task.recurrence.clone()} onchange={on_recurrence_change}/>
+                <TagInput data_test={"editing-tags"} id={"tags"} label={"Tags"} tags={task.tags.clone()} suggestions={(*suggested_tags).clone()} onadd={on_add_tag} onremove={on_remove_tag}/>
                 <TextInput data_test={"editing-description"} id={"description"} label={"Description"} control_type={ControlType::Textarea} rows={3} text={task.description.clone()} onchange={onchange.clone()}/>
+                <SubtaskEditor subtasks={task.subtasks.clone()} onchange={on_subtasks_change} data_test={"editing-subtasks"}/>
                 <Checkbox data_test={"completed"} id={"completed"} label={"Completed?"} checked={task.completed_at.is_some()} onchange={onchange.clone()}/>
                 <div class={button_style}>
                     <Button
@@ -210,7 +652,7 @@ pub fn task_details(props: &TaskDetailsProperties) -> Html {
                         hover_color={Color::Error2}
                         data_test={"cancel"}
                         onclick={toggle_edit.clone()}/>
-                    <Button label={"Save changes"} onclick={save_changes.clone()} data_test={"submit"}/>
+                    <Button label={"Save changes"} onclick={save_changes.clone()} data_test={"submit"} disabled={!form_errors.is_valid()}/>
                 </div>
             }
             else {
@@ -224,7 +666,31 @@ pub fn task_details(props: &TaskDetailsProperties) -> Html {
                         None => "-".to_string()
                     }
                 }/>
+                <TextDisplay
+                    data_test={"due-at"}
+                    label={"Due date"}
+                    text={match task.due_at() {
+                        Some(due_at) => due_at.with_timezone(&Local).format("%H:%M %d/%m/%y").to_string(),
+                        None => "No due date".to_string()
+                    }}/>
+                <TextDisplay
+                    data_test={"recurrence"}
+                    label={"Repeats"}
+                    text={match task.recurrence.as_ref() {
+                        Some(recurrence) => recurrence.to_string(),
+                        None => "Never".to_string()
+                    }}/>
+                if let Some(next_due_at) = task.next_due_at(Utc::now()).filter(|_| !task.completed()) {
+                    <TextDisplay
+                        data_test={"next-due-at"}
+                        label={"Next one due"}
+                        text={next_due_at.with_timezone(&Local).format("%H:%M %d/%m/%y").to_string()}/>
+                }
+                <TagList label={"Tags"} tags={task.tags.clone()} data_test={"tags"}/>
                 <TextDisplay data_test={"description"} label={"Description"} text={task.description.clone().unwrap_or_default()}/>
+                if !task.subtasks.is_empty() {
+                    <SubtaskChecklist subtasks={task.subtasks.clone()} ontoggle={toggle_subtask} data_test={"subtasks"}/>
+                }
                 <TextDisplay
                     data_test={"completed"}
                     label={"Completed at"}
@@ -234,6 +700,9 @@ pub fn task_details(props: &TaskDetailsProperties) -> Html {
                     }}/>
                 <div class={button_style}>
                     <Button data_test={"edit"} label={"Edit task"} onclick={toggle_edit.clone()}/>
+                    if task.subtask_progress().map_or(false, |(completed, total)| completed < total || !task.completed()) {
+                        <Button data_test={"complete-subtasks"} label={"Complete all subtasks"} onclick={complete_all_subtasks}/>
+                    }
                     <Button
                         data_test={"delete"}
                         label={"Delete task"}
@@ -249,17 +718,80 @@ pub fn task_details(props: &TaskDetailsProperties) -> Html {
     }
 }
 
-fn get_task_by_id(id: i32, store: Rc<TaskStore>) -> Option<TodoTask> {
-    let tasks = store.tasks.clone();
-    if let None = tasks {
-        return None;
+/// The merge is done with once it's saved or discarded, if it came from the outbox.
+fn forget_queued_merge(user_id: Option<u32>, task_id: i32) {
+    if let Some(user_id) = user_id {
+        Dispatch::<OutboxStore>::new().reduce_mut(|outbox| outbox.remove_merge(user_id, task_id));
     }
+}
 
-    tasks.clone().unwrap().iter().find_map(|task| {
-        if task.id == id {
-            Some(task.clone())
-        } else {
-            None
+fn update_task_optimistically(
+    token: String,
+    task: TodoTask,
+    snapshot: TodoTask,
+    user_id: Option<u32>,
+    task_dispatch: Dispatch<TaskStore>,
+    session_dispatch: Dispatch<SessionStore>,
+    error_data: UseStateHandle<ErrorData>,
+) {
+    // the edit is shown right away and rolled back if the server rejects it
+    task_dispatch.reduce_mut(|store| store.upsert_task(task.clone()));
+    spawn_local(async move {
+        let response = TasksService::update_task(token, task.clone()).await;
+        match response {
+            Ok(task) => task_dispatch.reduce_mut(|store| store.upsert_task(task)),
+            Err(error) => {
+                if let (Some(user_id), true) = (user_id, is_offline_error(&error)) {
+                    return defer_operation(user_id, PendingOperation::Update { task, base: Some(snapshot) });
+                }
+                task_dispatch.reduce_mut(|store| store.upsert_task(snapshot));
+                handle_api_error(error, &session_dispatch, Some(error_data))
+            }
+        }
+    })
+}
+
+/// Saves the checked off subtasks and then completes the task through the toggle endpoint,
+/// which unlike saving it creates the next instance of a recurring task.
+fn complete_all_subtasks_optimistically(
+    token: String,
+    snapshot: TodoTask,
+    user_id: Option<u32>,
+    task_dispatch: Dispatch<TaskStore>,
+    session_dispatch: Dispatch<SessionStore>,
+    error_data: UseStateHandle<ErrorData>,
+) {
+    let mut task = snapshot.clone();
+    task.complete_all_subtasks();
+    let update = PendingOperation::Update { task: task.clone(), base: Some(snapshot.clone()) };
+    let complete = (!task.completed()).then(|| PendingOperation::ToggleCompleted { task_id: task.id, completed: true });
+    if let Some(user_id) = user_id.filter(|user_id| should_queue(*user_id)) {
+        return [Some(update), complete].into_iter().flatten().for_each(|operation| queue_operation(user_id, operation));
+    }
+
+    // the change is shown right away and rolled back if the server rejects it
+    task_dispatch.reduce_mut(|store| [Some(&update), complete.as_ref()].into_iter().flatten().for_each(|operation| operation.apply(store)));
+    spawn_local(async move {
+        // a failed step leaves the task as the server last had it, with the operations it didn't get
+        let (rollback, unsent, response) = match TasksService::update_task(token.clone(), task).await {
+            Ok(saved) => match complete {
+                Some(complete) => {
+                    let response = TasksService::task_toggle_completed(token, saved.id).await;
+                    (saved, vec![complete], response)
+                }
+                None => (saved.clone(), vec![], Ok(saved)),
+            },
+            Err(error) => (snapshot, [Some(update), complete].into_iter().flatten().collect(), Err(error)),
+        };
+        match response {
+            Ok(task) => task_dispatch.reduce_mut(|store| store.upsert_task(task)),
+            Err(error) => {
+                if let (Some(user_id), true) = (user_id, is_offline_error(&error)) {
+                    return unsent.into_iter().for_each(|operation| defer_operation(user_id, operation));
+                }
+                task_dispatch.reduce_mut(|store| store.upsert_task(rollback));
+                handle_api_error(error, &session_dispatch, Some(error_data))
+            }
         }
     })
 }
@@ -292,3 +824,57 @@ pub fn get_priority_options() -> Vec<DropdownOption> {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::api_client::{ApiErrorKind, RequestInfo};
+
+    fn error(kind: ApiErrorKind) -> ApiError {
+        ApiError::new(kind, RequestInfo {
+            method: "GET".to_string(),
+            uri: "/tasks/1".to_string(),
+        })
+    }
+
+    fn http(status: u16, message: &str, code: Option<&str>) -> ApiError {
+        error(ApiErrorKind::Http {
+            status,
+            status_text: String::new(),
+            message: Some(message.to_string()),
+            code: code.map(str::to_string),
+            retry_after_ms: None,
+        })
+    }
+
+    #[test]
+    fn the_state_follows_the_status_not_the_message() {
+        assert_eq!(TaskState::from_error(&http(404, "", None)), Some(TaskState::NotFound));
+        assert_eq!(TaskState::from_error(&http(400, "Task not found!", None)), Some(TaskState::Failed));
+        assert_eq!(TaskState::from_error(&http(500, "Task is not owned by the user!", None)), Some(TaskState::Failed));
+    }
+
+    #[test]
+    fn only_a_task_of_another_user_is_forbidden() {
+        assert_eq!(
+            TaskState::from_error(&http(403, "", Some(NOT_OWNED_ERROR_CODE))),
+            Some(TaskState::Forbidden)
+        );
+    }
+
+    #[test]
+    fn an_invalid_token_ends_the_session() {
+        for status in [401, 403] {
+            assert_eq!(TaskState::from_error(&http(status, "", None)), Some(TaskState::Unauthenticated), "status {}", status);
+        }
+        assert_eq!(
+            TaskState::from_error(&http(403, "Token revoked", Some("unknown"))),
+            Some(TaskState::Unauthenticated)
+        );
+    }
+
+    #[test]
+    fn a_cancelled_fetch_has_no_state() {
+        assert_eq!(TaskState::from_error(&error(ApiErrorKind::Aborted)), None);
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use yewdux::prelude::*;
+
+use crate::{api::tasks::todo_task::TodoTask, SessionStore};
+
+/// Unsaved contents of a task form.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct TaskDraft {
+    pub user_id: u32,
+    pub task: TodoTask,
+    /// The task as it was when editing started, so the draft can still be merged with changes made elsewhere.
+    pub base: Option<TodoTask>,
+    pub as_completed: bool,
+}
+
+/// Drafts of the open forms keyed by their route, so they survive having to log in again.
+#[derive(Default, PartialEq, Clone, Debug, Store, Serialize, Deserialize)]
+#[store(storage = "session")]
+pub struct DraftStore {
+    drafts: HashMap<String, TaskDraft>,
+}
+
+impl DraftStore {
+    /// The draft of the form, if it was left by the same user.
+    pub fn get(&self, key: &str, user_id: u32) -> Option<&TaskDraft> {
+        self.drafts.get(key).filter(|draft| draft.user_id == user_id)
+    }
+
+    pub fn save(&mut self, key: &str, draft: TaskDraft) {
+        self.drafts.insert(key.to_string(), draft);
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.drafts.remove(key);
+    }
+
+    pub fn clear(&mut self) {
+        self.drafts.clear();
+    }
+}
+
+pub fn load_draft(key: &str, user_id: Option<u32>) -> Option<TaskDraft> {
+    Dispatch::<DraftStore>::new().get().get(key, user_id?).cloned()
+}
+
+pub fn save_draft(key: &str, draft: TaskDraft) {
+    Dispatch::<DraftStore>::new().reduce_mut(|drafts| drafts.save(key, draft));
+}
+
+pub fn remove_draft(key: &str) {
+    Dispatch::<DraftStore>::new().reduce_mut(|drafts| drafts.remove(key));
+}
+
+/// Called when a form is left, its draft is only kept if the session ended while it was open.
+pub fn leave_draft(key: &str) {
+    if Dispatch::<SessionStore>::new().get().user.is_some() {
+        remove_draft(key);
+    }
+}
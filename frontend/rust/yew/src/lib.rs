@@ -1,6 +1,6 @@
 use std::rc::Rc;
 
-use api::{auth::auth::Auth, tasks::todo_task::TodoTask};
+use api::auth::auth::Auth;
 use serde::{Deserialize, Serialize};
 use stylist::{
     style,
@@ -11,8 +11,12 @@ use yew_router::prelude::*;
 use yewdux::prelude::*;
 
 use crate::{
+    api::api_config::ApiConfig,
     app_context::AppContext,
-    components::molecules::theme_selector::ThemeSelector,
+    components::{
+        molecules::theme_selector::ThemeSelector,
+        organisms::{live_updates::LiveUpdates, outbox_status::OutboxStatus, session_expiry::SessionExpiry},
+    },
     router::{switch, Route},
     styles::color::Color,
 };
@@ -21,23 +25,38 @@ mod components;
 use components::organisms::navbar::Navbar;
 mod api;
 mod app_context;
+mod drafts;
+mod outbox;
+mod recovery;
 mod router;
+mod session_storage;
 mod styles;
+mod task_cache;
+mod task_store;
+mod task_sync;
 mod utils;
+mod validation;
+
+pub use recovery::install_recovery_screen;
+pub use task_store::TaskStore;
 
 const MAIN_STYLESHEET: &str = include_str!("main.css");
 
-#[derive(Default, PartialEq, Clone, Debug, Store, Serialize, Deserialize)]
-#[store(storage = "session", storage_tab_sync)]
+/// Kept in session storage, and also in local storage while "Remember me" is on, see `session_storage`.
+#[derive(Default, PartialEq, Clone, Debug, Serialize, Deserialize)]
 pub struct SessionStore {
     user: Option<Auth>,
     theme: Option<String>,
+    /// Where the user was when the session ended without logging out, they continue there after logging in.
+    #[serde(default)]
+    return_to: Option<String>,
+    /// Until when the login is remembered after the browser is closed, `None` for a session-only login.
+    #[serde(default)]
+    remember_until: Option<String>,
 }
 
-#[derive(Default, PartialEq, Clone, Debug, Store)]
-pub struct TaskStore {
-    tasks: Option<Vec<TodoTask>>,
-    tasks_valid: bool,
+pub async fn load_api_config() {
+    ApiConfig::init().await;
 }
 
 #[styled_component(App)]
@@ -94,6 +113,9 @@ pub fn app() -> Html {
             <Global css={css}/>
             <BrowserRouter>
                 <Navbar />
+                <SessionExpiry />
+                <OutboxStatus />
+                <LiveUpdates />
                 <div class={body_style}>
                     <Switch<Route> render={switch}/>
                 </div>
@@ -1,5 +1,9 @@
-use todo_project::App;
+use todo_project::{install_recovery_screen, load_api_config, App};
 
 fn main() {
-    yew::Renderer::<App>::new().render();
+    install_recovery_screen();
+    wasm_bindgen_futures::spawn_local(async {
+        load_api_config().await;
+        yew::Renderer::<App>::new().render();
+    });
 }
@@ -0,0 +1,600 @@
+use gloo::{timers::callback::Timeout, utils::window};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yewdux::prelude::*;
+
+use crate::{
+    api::{
+        api_client::{ApiError, ApiErrorKind},
+        auth::auth::Auth,
+        tasks::{task_merge::TaskMerge, tasks_service::TasksService, todo_task::TodoTask},
+    },
+    task_sync::tab_id,
+    utils::{handle_api_error, ErrorAction},
+    SessionStore, TaskStore,
+};
+
+/// How long to wait before replaying again after the server failed while we are online.
+const OUTBOX_RETRY_DELAY_MS: u32 = 10_000;
+/// How long a tab may replay without renewing its lock before another tab takes over.
+const REPLAY_LOCK_MS: f64 = 30_000.0;
+/// Temporary ids of different tabs differ in their last digits, see `OutboxStore::next_temp_id`.
+const TEMP_ID_TAB_SLOTS: i32 = 1000;
+
+/// A change to the tasks that still has to be sent to the server.
+/// Tasks created offline get a negative temporary id until the server assigns one.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum PendingOperation {
+    Create { task: TodoTask, as_completed: bool },
+    /// `base` is the task as it was before the edit, changes made to it elsewhere
+    /// in the meantime are merged on replay instead of being overwritten.
+    Update {
+        task: TodoTask,
+        #[serde(default)]
+        base: Option<TodoTask>,
+    },
+    /// `completed` is the state the user expects after the toggle.
+    ToggleCompleted { task_id: i32, completed: bool },
+    Delete { task_id: i32 },
+}
+
+impl PendingOperation {
+    pub fn task_id(&self) -> i32 {
+        match self {
+            PendingOperation::Create { task, .. } | PendingOperation::Update { task, .. } => task.id,
+            PendingOperation::ToggleCompleted { task_id, .. } | PendingOperation::Delete { task_id } => *task_id,
+        }
+    }
+
+    /// Shows the operation as done in the store, applying it twice has no further effect.
+    pub fn apply(&self, store: &mut TaskStore) {
+        match self {
+            PendingOperation::Create { task, .. } | PendingOperation::Update { task, .. } => store.upsert_task(task.clone()),
+            PendingOperation::ToggleCompleted { task_id, completed } => {
+                if let Some(mut task) = store.get_task(*task_id).cloned() {
+                    if task.completed() != *completed {
+                        task.toggle_completed();
+                        store.upsert_task(task);
+                    }
+                }
+            }
+            PendingOperation::Delete { task_id } => {
+                store.remove_task(*task_id);
+            }
+        }
+    }
+
+    fn describe(&self, store: &TaskStore) -> String {
+        let title = match self {
+            PendingOperation::Create { task, .. } | PendingOperation::Update { task, .. } => Some(task.title.clone()),
+            _ => store.get_task(self.task_id()).map(|task| task.title.clone()),
+        };
+        let title = title.unwrap_or_else(|| format!("task #{}", self.task_id()));
+
+        match self {
+            PendingOperation::Create { .. } => format!("Creating \"{}\"", title),
+            PendingOperation::Update { .. } => format!("Saving \"{}\"", title),
+            PendingOperation::ToggleCompleted { .. } => format!("Completing \"{}\"", title),
+            PendingOperation::Delete { .. } => format!("Deleting \"{}\"", title),
+        }
+    }
+
+    async fn send(&self, token: String) -> Result<Replayed, ApiError> {
+        let task = match self {
+            PendingOperation::Create { task, as_completed } => {
+                TasksService::create_task(token, task.clone(), *as_completed).await?
+            }
+            PendingOperation::Update { task, base: Some(base) } => {
+                let remote = TasksService::get_task(token.clone(), task.id, None).await?;
+                let merge = TaskMerge::new(base.clone(), task.clone(), remote);
+                if merge.has_conflicts() {
+                    return Ok(Replayed::Conflict(merge));
+                }
+                TasksService::update_task(token, merge.merged).await?
+            }
+            PendingOperation::Update { task, base: None } => TasksService::update_task(token, task.clone()).await?,
+            PendingOperation::ToggleCompleted { task_id, .. } => {
+                TasksService::task_toggle_completed(token, *task_id).await?
+            }
+            PendingOperation::Delete { task_id } => {
+                TasksService::delete_task(token, *task_id).await?;
+                return Ok(Replayed::Sent(None));
+            }
+        };
+        Ok(Replayed::Sent(Some(task)))
+    }
+}
+
+/// What became of an operation sent to the server.
+enum Replayed {
+    /// The task as the server has it now, `None` if it was deleted.
+    Sent(Option<TodoTask>),
+    /// The task was changed elsewhere in a way that conflicts with the queued edit.
+    Conflict(TaskMerge),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct OutboxEntry {
+    pub id: Uuid,
+    pub user_id: u32,
+    pub operation: PendingOperation,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct QueuedMerge {
+    pub user_id: u32,
+    pub merge: TaskMerge,
+}
+
+/// The tab that is replaying the queue, the others leave it alone until the lock expires.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct ReplayLock {
+    tab: Uuid,
+    /// Milliseconds since the epoch.
+    expires_at: f64,
+}
+
+/// Operations made while offline, kept across reloads until they are replayed.
+/// Every tab works on the same queue, changes made by one are loaded by the others.
+#[derive(Default, PartialEq, Clone, Debug, Store, Serialize, Deserialize)]
+#[store(storage = "local", storage_tab_sync)]
+#[serde(default)]
+pub struct OutboxStore {
+    entries: Vec<OutboxEntry>,
+    /// Temporary ids handed out by all tabs so far.
+    temp_id_counter: i32,
+    /// Operations the server rejected on replay, shown until dismissed.
+    conflicts: Vec<String>,
+    /// Queued edits that conflicted with changes made elsewhere, resolved on the task's page.
+    merges: Vec<QueuedMerge>,
+    replay_lock: Option<ReplayLock>,
+}
+
+impl OutboxStore {
+    pub fn pending(&self, user_id: u32) -> Vec<PendingOperation> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.user_id == user_id)
+            .map(|entry| entry.operation.clone())
+            .collect()
+    }
+
+    pub fn has_pending(&self, user_id: u32) -> bool {
+        self.entries.iter().any(|entry| entry.user_id == user_id)
+    }
+
+    pub fn conflicts(&self) -> &[String] {
+        &self.conflicts
+    }
+
+    pub fn merge(&self, user_id: u32, task_id: i32) -> Option<&TaskMerge> {
+        self.merges
+            .iter()
+            .find(|queued| queued.user_id == user_id && queued.merge.local.id == task_id)
+            .map(|queued| &queued.merge)
+    }
+
+    pub fn remove_merge(&mut self, user_id: u32, task_id: i32) {
+        self.merges
+            .retain(|queued| queued.user_id != user_id || queued.merge.local.id != task_id);
+    }
+
+    /// Keeps the merge until the user resolves it, in place of an older one of the same task.
+    fn add_merge(&mut self, user_id: u32, merge: TaskMerge) {
+        self.remove_merge(user_id, merge.local.id);
+        self.merges.push(QueuedMerge { user_id, merge });
+    }
+
+    /// Temporary ids are made of a counter shared by the tabs and the slot of the tab,
+    /// so two tabs queueing a task at the same moment still hand out different ids.
+    fn next_temp_id(&mut self, tab_slot: i32) -> i32 {
+        self.temp_id_counter = self.temp_id_counter % (i32::MAX / TEMP_ID_TAB_SLOTS - 1) + 1;
+        -(self.temp_id_counter * TEMP_ID_TAB_SLOTS + tab_slot)
+    }
+
+    /// Takes the replay lock for the tab, `false` if a replay is already running in any tab.
+    fn lock_replay(&mut self, tab: Uuid, now: f64) -> bool {
+        if self.replay_lock.as_ref().map_or(false, |lock| lock.expires_at > now) {
+            return false;
+        }
+        self.replay_lock = Some(ReplayLock {
+            tab,
+            expires_at: now + REPLAY_LOCK_MS,
+        });
+        true
+    }
+
+    /// Extends the tab's replay lock, `false` if it expired and another tab took over.
+    fn renew_replay_lock(&mut self, tab: Uuid, now: f64) -> bool {
+        match self.replay_lock.as_mut() {
+            Some(lock) if lock.tab == tab => {
+                lock.expires_at = now + REPLAY_LOCK_MS;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn unlock_replay(&mut self, tab: Uuid) {
+        if self.replay_lock.as_ref().map_or(false, |lock| lock.tab == tab) {
+            self.replay_lock = None;
+        }
+    }
+
+    pub fn enqueue(&mut self, user_id: u32, operation: PendingOperation) {
+        // a task that never reached the server can simply be forgotten
+        if let PendingOperation::Delete { task_id } = operation {
+            if task_id < 0 {
+                self.entries
+                    .retain(|entry| entry.user_id != user_id || entry.operation.task_id() != task_id);
+                return;
+            }
+        }
+
+        self.entries.push(OutboxEntry {
+            id: Uuid::new_v4(),
+            user_id,
+            operation,
+        });
+    }
+
+    pub fn dismiss_conflicts(&mut self) {
+        self.conflicts.clear();
+    }
+
+    /// Forgets everything queued by the user, e.g. when they log out.
+    pub fn clear(&mut self, user_id: u32) {
+        self.entries.retain(|entry| entry.user_id != user_id);
+        self.merges.retain(|queued| queued.user_id != user_id);
+        self.conflicts.clear();
+    }
+
+    fn remove(&mut self, id: Uuid) {
+        self.entries.retain(|entry| entry.id != id);
+    }
+
+    /// Points the remaining operations on a task created offline to its server id.
+    fn map_task_id(&mut self, user_id: u32, temp_id: i32, task_id: i32) {
+        for entry in self.entries.iter_mut().filter(|entry| entry.user_id == user_id) {
+            match &mut entry.operation {
+                PendingOperation::Create { task, .. } => {
+                    if task.id == temp_id {
+                        task.id = task_id;
+                    }
+                }
+                PendingOperation::Update { task, base } => {
+                    for task in std::iter::once(task).chain(base.as_mut()) {
+                        if task.id == temp_id {
+                            task.id = task_id;
+                        }
+                    }
+                }
+                PendingOperation::ToggleCompleted { task_id: id, .. } | PendingOperation::Delete { task_id: id } => {
+                    if *id == temp_id {
+                        *id = task_id;
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn is_offline() -> bool {
+    !window().navigator().on_line()
+}
+
+/// Whether the operation should go to the outbox instead of the server, either because
+/// we are offline or because earlier operations are still waiting and must be sent first.
+pub fn should_queue(user_id: u32) -> bool {
+    is_offline() || Dispatch::<OutboxStore>::new().get().has_pending(user_id)
+}
+
+/// Whether a failed request should be queued for later instead of being rolled back.
+/// Only a lost connection counts, a server that's briefly unavailable is reported as usual.
+pub fn is_offline_error(error: &ApiError) -> bool {
+    is_offline() || matches!(error.kind, ApiErrorKind::Network(_))
+}
+
+/// Queues the operation and shows it as applied right away.
+pub fn queue_operation(user_id: u32, operation: PendingOperation) {
+    Dispatch::<TaskStore>::new().reduce_mut(|store| operation.apply(store));
+    Dispatch::<OutboxStore>::new().reduce_mut(|outbox| outbox.enqueue(user_id, operation));
+}
+
+/// Queues a new task under a temporary id, it gets its real id once the server created it.
+pub fn queue_create(user_id: u32, mut task: TodoTask, as_completed: bool) {
+    let mut temp_id = 0;
+    let tab_slot = (tab_id().as_u128() % TEMP_ID_TAB_SLOTS as u128) as i32;
+    Dispatch::<OutboxStore>::new().reduce_mut(|outbox| temp_id = outbox.next_temp_id(tab_slot));
+    task.create_locally(temp_id, user_id as i32, as_completed);
+    queue_operation(user_id, PendingOperation::Create { task, as_completed });
+}
+
+/// Queues an operation that is already shown as applied, after its request failed while offline.
+pub fn defer_operation(user_id: u32, operation: PendingOperation) {
+    Dispatch::<OutboxStore>::new().reduce_mut(|outbox| outbox.enqueue(user_id, operation));
+}
+
+/// Re-applies the queued operations on top of tasks that came from the server or the cache.
+pub fn apply_pending(store: &mut TaskStore, user_id: u32) {
+    for operation in Dispatch::<OutboxStore>::new().get().pending(user_id) {
+        operation.apply(store);
+    }
+}
+
+/// Sends the user's queued operations in order. Stops at the first request that fails
+/// because we are still offline, rejected operations are dropped and reported as conflicts.
+/// Only one tab replays at a time.
+pub async fn replay_outbox(user: Auth) {
+    let outbox_dispatch = Dispatch::<OutboxStore>::new();
+    let task_dispatch = Dispatch::<TaskStore>::new();
+    let tab = tab_id();
+
+    let mut started = false;
+    outbox_dispatch.reduce_mut(|outbox| started = outbox.lock_replay(tab, js_sys::Date::now()));
+    if !started {
+        return;
+    }
+
+    let mut sent = false;
+    loop {
+        let mut locked = false;
+        outbox_dispatch.reduce_mut(|outbox| locked = outbox.renew_replay_lock(tab, js_sys::Date::now()));
+        if !locked {
+            break;
+        }
+
+        let entry = outbox_dispatch
+            .get()
+            .entries
+            .iter()
+            .find(|entry| entry.user_id == user.id)
+            .cloned();
+        let Some(entry) = entry else {
+            break;
+        };
+
+        match entry.operation.send(user.token.clone()).await {
+            Ok(Replayed::Conflict(merge)) => {
+                let conflict = format!(
+                    "{} failed: the task was changed elsewhere, open it to pick the changes to keep",
+                    entry.operation.describe(&task_dispatch.get())
+                );
+                outbox_dispatch.reduce_mut(|outbox| {
+                    outbox.remove(entry.id);
+                    outbox.add_merge(user.id, merge);
+                    outbox.conflicts.push(conflict);
+                });
+                sent = true;
+            }
+            Ok(Replayed::Sent(task)) => {
+                sent = true;
+                outbox_dispatch.reduce_mut(|outbox| outbox.remove(entry.id));
+                match (&entry.operation, task) {
+                    (PendingOperation::Create { task: local_task, .. }, Some(task)) => {
+                        outbox_dispatch.reduce_mut(|outbox| outbox.map_task_id(user.id, local_task.id, task.id));
+                        task_dispatch.reduce_mut(|store| {
+                            store.remove_task(local_task.id);
+                            store.upsert_task(task);
+                        });
+                    }
+                    (PendingOperation::ToggleCompleted { completed, .. }, Some(task)) if task.completed() != *completed => {
+                        let conflict = format!(
+                            "{} failed: the task was changed elsewhere in the meantime",
+                            entry.operation.describe(&task_dispatch.get())
+                        );
+                        outbox_dispatch.reduce_mut(|outbox| outbox.conflicts.push(conflict));
+                    }
+                    _ => (),
+                }
+            }
+            Err(error) => match ErrorAction::from(&error) {
+                ErrorAction::Show => {
+                    let conflict = format!("{} failed: {}", entry.operation.describe(&task_dispatch.get()), error);
+                    let task_id = entry.operation.task_id();
+                    outbox_dispatch.reduce_mut(|outbox| {
+                        outbox.remove(entry.id);
+                        // later operations on a task that couldn't be created would fail as well
+                        if let PendingOperation::Create { .. } = entry.operation {
+                            outbox
+                                .entries
+                                .retain(|entry| entry.user_id != user.id || entry.operation.task_id() != task_id);
+                        }
+                        outbox.conflicts.push(conflict);
+                    });
+                    sent = true;
+                }
+                action => {
+                    // the online event won't come while we are online, so the replay is tried again later
+                    if action == ErrorAction::Retry && !is_offline() {
+                        let user = user.clone();
+                        Timeout::new(OUTBOX_RETRY_DELAY_MS, move || spawn_local(replay_outbox(user))).forget();
+                    }
+                    handle_api_error(error, &Dispatch::<SessionStore>::new(), None);
+                    break;
+                }
+            },
+        }
+    }
+
+    outbox_dispatch.reduce_mut(|outbox| outbox.unlock_replay(tab));
+    // the list is fetched again to pick up what the server made of our changes
+    if sent {
+        task_dispatch.reduce_mut(|store| store.invalidate_list());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: u32 = 1;
+    const OTHER_USER: u32 = 2;
+
+    fn task(id: i32) -> TodoTask {
+        TodoTask {
+            id,
+            title: format!("Task {}", id),
+            ..Default::default()
+        }
+    }
+
+    fn update(id: i32) -> PendingOperation {
+        PendingOperation::Update { task: task(id), base: Some(task(id)) }
+    }
+
+    fn queued(outbox: &OutboxStore, user_id: u32) -> Vec<i32> {
+        outbox.pending(user_id).iter().map(|operation| operation.task_id()).collect()
+    }
+
+    #[test]
+    fn operations_are_queued_in_order() {
+        let mut outbox = OutboxStore::default();
+        outbox.enqueue(USER, update(1));
+        outbox.enqueue(OTHER_USER, update(2));
+        outbox.enqueue(USER, PendingOperation::Delete { task_id: 3 });
+
+        assert_eq!(queued(&outbox, USER), vec![1, 3]);
+        assert_eq!(queued(&outbox, OTHER_USER), vec![2]);
+    }
+
+    #[test]
+    fn deleting_a_task_created_offline_forgets_it() {
+        let mut outbox = OutboxStore::default();
+        outbox.enqueue(USER, PendingOperation::Create { task: task(-1), as_completed: false });
+        outbox.enqueue(USER, update(-1));
+        outbox.enqueue(USER, update(5));
+        outbox.enqueue(OTHER_USER, update(-1));
+
+        outbox.enqueue(USER, PendingOperation::Delete { task_id: -1 });
+
+        assert_eq!(queued(&outbox, USER), vec![5]);
+        assert_eq!(queued(&outbox, OTHER_USER), vec![-1]);
+    }
+
+    #[test]
+    fn deleting_a_task_of_the_server_is_queued() {
+        let mut outbox = OutboxStore::default();
+        outbox.enqueue(USER, update(5));
+        outbox.enqueue(USER, PendingOperation::Delete { task_id: 5 });
+
+        assert_eq!(queued(&outbox, USER), vec![5, 5]);
+    }
+
+    #[test]
+    fn a_created_task_takes_its_server_id() {
+        let mut outbox = OutboxStore::default();
+        outbox.enqueue(USER, update(-1));
+        outbox.enqueue(USER, PendingOperation::ToggleCompleted { task_id: -1, completed: true });
+        outbox.enqueue(USER, update(-1 - TEMP_ID_TAB_SLOTS));
+        outbox.enqueue(USER, update(-2));
+        outbox.enqueue(OTHER_USER, update(-1));
+
+        outbox.map_task_id(USER, -1, 42);
+
+        assert_eq!(queued(&outbox, USER), vec![42, 42, -1 - TEMP_ID_TAB_SLOTS, -2]);
+        assert_eq!(queued(&outbox, OTHER_USER), vec![-1]);
+        match &outbox.pending(USER)[0] {
+            PendingOperation::Update { task, base } => {
+                assert_eq!(task.id, 42);
+                assert_eq!(base.as_ref().map(|base| base.id), Some(42));
+            }
+            operation => panic!("unexpected operation {:?}", operation),
+        }
+    }
+
+    #[test]
+    fn temp_ids_are_negative_and_differ_between_tabs() {
+        let mut outbox = OutboxStore::default();
+        let first = outbox.next_temp_id(0);
+        // another tab allocating from the same counter at the same moment
+        let mut other_tab = outbox.clone();
+        let second = outbox.next_temp_id(0);
+        let other = other_tab.next_temp_id(7);
+
+        assert!(first < 0 && second < 0 && other < 0);
+        assert_ne!(first, second);
+        assert_ne!(second, other);
+    }
+
+    #[test]
+    fn temp_ids_stay_negative_when_the_counter_wraps() {
+        let mut outbox = OutboxStore {
+            temp_id_counter: i32::MAX / TEMP_ID_TAB_SLOTS - 2,
+            ..Default::default()
+        };
+
+        assert!(outbox.next_temp_id(TEMP_ID_TAB_SLOTS - 1) < 0);
+        assert_eq!(outbox.next_temp_id(0), -TEMP_ID_TAB_SLOTS);
+    }
+
+    #[test]
+    fn only_one_tab_replays_at_a_time() {
+        let (tab, other_tab) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut outbox = OutboxStore::default();
+
+        assert!(outbox.lock_replay(tab, 0.0));
+        assert!(!outbox.lock_replay(tab, 1.0));
+        assert!(!outbox.lock_replay(other_tab, 1.0));
+        assert!(!outbox.renew_replay_lock(other_tab, 1.0));
+
+        outbox.unlock_replay(other_tab);
+        assert!(!outbox.lock_replay(other_tab, 1.0));
+        outbox.unlock_replay(tab);
+        assert!(outbox.lock_replay(other_tab, 1.0));
+    }
+
+    #[test]
+    fn an_expired_replay_lock_is_taken_over() {
+        let (tab, other_tab) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut outbox = OutboxStore::default();
+        outbox.lock_replay(tab, 0.0);
+
+        assert!(outbox.renew_replay_lock(tab, REPLAY_LOCK_MS - 1.0));
+        assert!(!outbox.lock_replay(other_tab, REPLAY_LOCK_MS));
+        assert!(outbox.lock_replay(other_tab, 2.0 * REPLAY_LOCK_MS));
+        assert!(!outbox.renew_replay_lock(tab, 2.0 * REPLAY_LOCK_MS));
+    }
+
+    #[test]
+    fn a_newer_merge_of_the_same_task_replaces_the_older_one() {
+        let mut outbox = OutboxStore::default();
+        let mut remote = task(1);
+        remote.title = "Changed elsewhere".to_string();
+        outbox.add_merge(USER, TaskMerge::new(task(1), task(1), task(1)));
+        outbox.add_merge(USER, TaskMerge::new(task(1), task(1), remote.clone()));
+
+        assert_eq!(outbox.merges.len(), 1);
+        assert_eq!(outbox.merge(USER, 1).map(|merge| &merge.remote), Some(&remote));
+        assert_eq!(outbox.merge(OTHER_USER, 1), None);
+
+        outbox.remove_merge(USER, 1);
+        assert_eq!(outbox.merge(USER, 1), None);
+    }
+
+    #[test]
+    fn clearing_forgets_only_the_users_operations() {
+        let mut outbox = OutboxStore::default();
+        outbox.enqueue(USER, update(1));
+        outbox.enqueue(OTHER_USER, update(2));
+        outbox.add_merge(USER, TaskMerge::new(task(1), task(1), task(1)));
+
+        outbox.clear(USER);
+
+        assert!(!outbox.has_pending(USER));
+        assert!(outbox.has_pending(OTHER_USER));
+        assert_eq!(outbox.merge(USER, 1), None);
+    }
+
+    #[test]
+    fn queued_updates_without_a_base_are_still_read() {
+        let json = serde_json::to_string(&PendingOperation::Update { task: task(1), base: None }).unwrap();
+        let legacy = json.replace(",\"base\":null", "");
+
+        assert_eq!(
+            serde_json::from_str::<PendingOperation>(&legacy).unwrap(),
+            PendingOperation::Update { task: task(1), base: None }
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use std::panic;
+
+use gloo::{
+    console::error,
+    utils::{document, window},
+};
+
+/// Plain HTML, nothing of the app can be relied on anymore once it panicked.
+const RECOVERY_SCREEN: &str = r#"
+<div data-test="recovery-screen" style="padding: 1em; margin: 10vh auto; width: min(80vw, 850px); text-align: center;">
+    <h2>Something went wrong</h2>
+    <p>The app ran into an unexpected error and had to stop. Changes that were already saved are safe.</p>
+    <p>
+        <a data-test="recovery-reload" href="{href}">Reload this page</a>
+        or
+        <a data-test="recovery-home" href="/">go back to your tasks</a>
+    </p>
+</div>
+"#;
+
+/// Yew can't recover from a panic, whatever failed (a missing context, a style that didn't parse, ..)
+/// leaves a dead app behind, so it's replaced with a screen that lets the user start over.
+pub fn install_recovery_screen() {
+    panic::set_hook(Box::new(|info| {
+        error!(info.to_string());
+        show_recovery_screen();
+    }));
+}
+
+fn show_recovery_screen() {
+    let href = window().location().href().unwrap_or_else(|_| "/".to_string());
+    if let Some(body) = document().body() {
+        body.set_inner_html(&RECOVERY_SCREEN.replace("{href}", &escape_attribute(&href)));
+    }
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
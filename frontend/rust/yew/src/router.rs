@@ -1,9 +1,15 @@
-use crate::components::pages::{
-    create_account::CreateAccount, home::Home, login::Login, new_task::NewTask,
-    task_details::TaskDetails,
+use crate::{
+    components::pages::{
+        create_account::CreateAccount, home::Home, login::Login, new_task::NewTask,
+        not_found::NotFound, task_details::TaskDetails,
+    },
+    SessionStore,
 };
+use gloo::console::log;
+use serde::{Deserialize, Serialize};
 use yew::prelude::*;
 use yew_router::prelude::*;
+use yewdux::prelude::*;
 
 #[derive(Clone, Routable, PartialEq)]
 pub enum Route {
@@ -17,14 +23,89 @@ pub enum Route {
     TaskDetails { id: i32 },
     #[at("/tasks/new")]
     NewTask,
+    #[not_found]
+    #[at("/404")]
+    NotFound,
+}
+
+/// Query of the login page, `next` is the path to go to once logged in.
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Debug)]
+pub struct LoginQuery {
+    pub next: Option<String>,
 }
 
 pub fn switch(route: Route) -> Html {
     match route {
         Route::Home => html! {<Home />},
-        Route::CreateAccount => html! {<CreateAccount />},
-        Route::Login => html! {<Login />},
-        Route::TaskDetails { id } => html! { <TaskDetails task_id={id}/>},
-        Route::NewTask => html! { <NewTask /> },
+        Route::CreateAccount => html! {<GuestOnly><CreateAccount /></GuestOnly>},
+        Route::Login => html! {<GuestOnly><Login /></GuestOnly>},
+        Route::TaskDetails { id } => html! {<RequireAuth><TaskDetails task_id={id}/></RequireAuth>},
+        Route::NewTask => html! {<RequireAuth><NewTask /></RequireAuth>},
+        Route::NotFound => html! {<NotFound />},
+    }
+}
+
+/// Sends the user to the login page, which brings them back to `next` afterwards.
+pub fn redirect_to_login(navigator: &Navigator, next: Option<String>) {
+    if let Err(error) = navigator.replace_with_query(&Route::Login, &LoginQuery { next }) {
+        log!(format!("Unable to redirect to the login page: {:?}", error));
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct GuardProperties {
+    pub children: Children,
+}
+
+/// Renders the page only for logged in users, everyone else has to log in first.
+#[function_component(RequireAuth)]
+fn require_auth(props: &GuardProperties) -> Html {
+    let logged_in = use_selector(|store: &SessionStore| store.user.is_some());
+    let navigator = use_navigator().unwrap();
+    let location = use_location().unwrap();
+
+    use_effect_with_deps(
+        move |logged_in| {
+            if !*logged_in {
+                redirect_to_login(&navigator, Some(location.path().to_string()));
+            }
+            || ()
+        },
+        *logged_in,
+    );
+
+    match *logged_in {
+        true => html! {<>{for props.children.iter()}</>},
+        false => html! {},
+    }
+}
+
+/// Renders the page only for anonymous users, logged in users go where the login page was told to send them.
+#[function_component(GuestOnly)]
+fn guest_only(props: &GuardProperties) -> Html {
+    let logged_in = use_selector(|store: &SessionStore| store.user.is_some());
+    let navigator = use_navigator().unwrap();
+    let location = use_location().unwrap();
+
+    use_effect_with_deps(
+        move |logged_in| {
+            if *logged_in {
+                // only paths of our own routes are followed
+                let next = location
+                    .query::<LoginQuery>()
+                    .ok()
+                    .and_then(|query| query.next)
+                    .and_then(|next| Route::recognize(&next))
+                    .unwrap_or(Route::Home);
+                navigator.replace(&next);
+            }
+            || ()
+        },
+        *logged_in,
+    );
+
+    match *logged_in {
+        true => html! {},
+        false => html! {<>{for props.children.iter()}</>},
     }
 }
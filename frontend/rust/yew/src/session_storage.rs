@@ -0,0 +1,134 @@
+use std::{any::type_name, rc::Rc};
+
+use chrono::{DateTime, Utc};
+use gloo::{console::log, events::EventListener, utils::window};
+use wasm_bindgen::JsCast;
+use web_sys::StorageEvent;
+use yewdux::{
+    listener::{init_listener, Listener},
+    prelude::*,
+    storage::{self, Area},
+};
+
+use crate::{api::auth::auth::Auth, SessionStore};
+
+impl Store for SessionStore {
+    fn new() -> Self {
+        let store = load_session();
+        init_listener(SessionPersistence {
+            user: store.user.clone(),
+        });
+        EventListener::new(&window(), "storage", on_storage).forget();
+        store
+    }
+
+    fn should_notify(&self, old: &Self) -> bool {
+        self != old
+    }
+}
+
+impl SessionStore {
+    pub fn remembered(&self) -> bool {
+        let remember_until = match &self.remember_until {
+            Some(remember_until) => remember_until,
+            None => return false,
+        };
+
+        match DateTime::parse_from_rfc3339(remember_until) {
+            Ok(remember_until) => remember_until.with_timezone(&Utc) > Utc::now(),
+            Err(_) => false,
+        }
+    }
+}
+
+/// The session of this tab, or a remembered login if this tab isn't logged in.
+fn load_session() -> SessionStore {
+    let session = storage::load::<SessionStore>(Area::Session).ok().flatten();
+    if let Some(session) = session.as_ref().filter(|session| session.user.is_some()) {
+        return session.clone();
+    }
+
+    match load_remembered() {
+        Some(remembered) => remembered,
+        None => session.unwrap_or_default(),
+    }
+}
+
+fn load_remembered() -> Option<SessionStore> {
+    let stored = storage::load::<SessionStore>(Area::Local).ok().flatten()?;
+    if stored.user.is_some() && !stored.remembered() {
+        forget_login(&stored);
+        return None;
+    }
+
+    Some(stored).filter(|stored| stored.user.is_some())
+}
+
+/// Replaces the remembered login with a logged out state, which also logs out the other tabs that share it.
+fn forget_login(store: &SessionStore) {
+    let logged_out = SessionStore {
+        theme: store.theme.clone(),
+        ..Default::default()
+    };
+    if let Err(error) = storage::save(&logged_out, Area::Local) {
+        log!(format!("Unable to forget the remembered login: {:?}", error));
+    }
+}
+
+/// Writes the session to session storage, and to local storage while the login is remembered.
+struct SessionPersistence {
+    user: Option<Auth>,
+}
+
+impl Listener for SessionPersistence {
+    type Store = SessionStore;
+
+    fn on_change(&mut self, store: Rc<SessionStore>) {
+        let previous_user = std::mem::replace(&mut self.user, store.user.clone());
+        if let Err(error) = storage::save(&*store, Area::Session) {
+            log!(format!("Unable to save the session: {:?}", error));
+        }
+
+        if store.user.is_some() && store.remember_until.is_some() {
+            if let Err(error) = storage::save(&*store, Area::Local) {
+                log!(format!("Unable to remember the login: {:?}", error));
+            }
+            return;
+        }
+
+        // logging out or a rejected token ends the remembered login too, unless it's someone else's
+        if let (Some(previous_user), None) = (previous_user, &store.user) {
+            let remembered = storage::load::<SessionStore>(Area::Local).ok().flatten();
+            if let Some(remembered) = remembered.filter(|remembered| remembered.user.as_ref() == Some(&previous_user)) {
+                forget_login(&remembered);
+            }
+        }
+    }
+}
+
+/// Follows logins and logouts of remembered sessions made in other tabs.
+/// Tabs with a session-only login keep it.
+fn on_storage(event: &web_sys::Event) {
+    let event = match event.dyn_ref::<StorageEvent>() {
+        Some(event) => event,
+        None => return,
+    };
+    if event.key().as_deref() != Some(type_name::<SessionStore>()) {
+        return;
+    }
+
+    let remote = event
+        .new_value()
+        .and_then(|json| serde_json::from_str::<SessionStore>(&json).ok())
+        .unwrap_or_default();
+    let dispatch = Dispatch::<SessionStore>::new();
+    let current = dispatch.get();
+    if current.user.is_some() && current.remember_until.is_none() {
+        return;
+    }
+
+    dispatch.reduce_mut(|store| {
+        store.user = remote.user;
+        store.remember_until = remote.remember_until;
+    });
+}
@@ -0,0 +1,179 @@
+use std::cell::RefCell;
+
+use chrono::Utc;
+use gloo::{
+    console::log,
+    storage::{LocalStorage, Storage},
+    timers::callback::Timeout,
+    utils::window,
+};
+use js_sys::Promise;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::{future_to_promise, spawn_local, JsFuture};
+use web_sys::{IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode};
+
+use crate::api::tasks::todo_task::TodoTask;
+
+const DB_NAME: &str = "todo-app";
+const DB_VERSION: u32 = 1;
+const TASKS_STORE_NAME: &str = "tasks";
+const LOCAL_STORAGE_PREFIX: &str = "todo-tasks-";
+/// Changes made within this many milliseconds of each other are written once.
+const SAVE_DEBOUNCE_MS: u32 = 500;
+
+thread_local! {
+    /// Resolves to the connection, which is opened on first use and kept for the lifetime of the page.
+    static DATABASE: RefCell<Option<Promise>> = RefCell::new(None);
+    /// The timer of the write that is waiting for the tasks to stop changing.
+    static PENDING_SAVE: RefCell<Option<Timeout>> = RefCell::new(None);
+}
+
+/// Tasks of a single user as they were last fetched from the backend.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct CachedTasks {
+    pub tasks: Vec<TodoTask>,
+    /// RFC 3339 timestamp of when the tasks were saved.
+    pub saved_at: String,
+}
+
+impl CachedTasks {
+    pub fn new(tasks: Vec<TodoTask>) -> Self {
+        Self {
+            tasks,
+            saved_at: Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Persists the task list per user in IndexedDB, falling back to localStorage
+/// where IndexedDB is unavailable, e.g. in some private browsing modes.
+pub struct TaskCache;
+
+impl TaskCache {
+    pub async fn load(user_id: u32) -> Option<CachedTasks> {
+        let json = match Self::idb_get(user_id).await {
+            Ok(value) => value.as_string(),
+            Err(error) => {
+                log!("IndexedDB unavailable, reading tasks from localStorage:", error);
+                LocalStorage::get::<String>(Self::local_storage_key(user_id)).ok()
+            }
+        }?;
+
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Saves the tasks once they stopped changing for a moment, only the latest ones are written.
+    pub fn schedule_save(user_id: u32, cached: CachedTasks) {
+        let timeout = Timeout::new(SAVE_DEBOUNCE_MS, move || {
+            spawn_local(async move { Self::save(user_id, &cached).await });
+        });
+        // dropping the previous timer cancels its write
+        PENDING_SAVE.with(|pending| pending.borrow_mut().replace(timeout));
+    }
+
+    async fn save(user_id: u32, cached: &CachedTasks) {
+        let json = match serde_json::to_string(cached) {
+            Ok(json) => json,
+            Err(_) => return,
+        };
+
+        if let Err(error) = Self::idb_put(user_id, &json).await {
+            log!("IndexedDB unavailable, saving tasks to localStorage:", error);
+            let _ = LocalStorage::set(Self::local_storage_key(user_id), json);
+        }
+    }
+
+    /// Removes the user's tasks from both storages, whichever one was used.
+    pub async fn clear(user_id: u32) {
+        PENDING_SAVE.with(|pending| pending.borrow_mut().take());
+        if let Err(error) = Self::idb_delete(user_id).await {
+            log!("Unable to clear cached tasks from IndexedDB:", error);
+        }
+        LocalStorage::delete(Self::local_storage_key(user_id));
+    }
+
+    fn local_storage_key(user_id: u32) -> String {
+        format!("{}{}", LOCAL_STORAGE_PREFIX, user_id)
+    }
+
+    async fn idb_get(user_id: u32) -> Result<JsValue, JsValue> {
+        let store = Self::object_store(IdbTransactionMode::Readonly).await?;
+        request_result(&store.get(&JsValue::from(user_id))?).await
+    }
+
+    async fn idb_put(user_id: u32, json: &str) -> Result<(), JsValue> {
+        let store = Self::object_store(IdbTransactionMode::Readwrite).await?;
+        request_result(&store.put_with_key(&JsValue::from_str(json), &JsValue::from(user_id))?).await?;
+        Ok(())
+    }
+
+    async fn idb_delete(user_id: u32) -> Result<(), JsValue> {
+        let store = Self::object_store(IdbTransactionMode::Readwrite).await?;
+        request_result(&store.delete(&JsValue::from(user_id))?).await?;
+        Ok(())
+    }
+
+    async fn object_store(mode: IdbTransactionMode) -> Result<IdbObjectStore, JsValue> {
+        let transaction = Self::database()
+            .await?
+            .transaction_with_str_and_mode(TASKS_STORE_NAME, mode);
+        match transaction {
+            Ok(transaction) => transaction.object_store(TASKS_STORE_NAME),
+            Err(error) => {
+                // the browser closed the connection, the next call opens a new one
+                Self::forget_database();
+                Err(error)
+            }
+        }
+    }
+
+    async fn database() -> Result<IdbDatabase, JsValue> {
+        let open = DATABASE.with(|database| database.borrow_mut().get_or_insert_with(open_database).clone());
+        match JsFuture::from(open).await {
+            Ok(db) => Ok(db.unchecked_into()),
+            Err(error) => {
+                Self::forget_database();
+                Err(error)
+            }
+        }
+    }
+
+    fn forget_database() {
+        DATABASE.with(|database| database.borrow_mut().take());
+    }
+}
+
+/// Starts opening the database, creating its object store on the first run.
+fn open_database() -> Promise {
+    let open = || -> Result<Promise, JsValue> {
+        let factory = window()
+            .indexed_db()?
+            .ok_or_else(|| JsValue::from_str("IndexedDB is not supported"))?;
+        let open_request = factory.open_with_u32(DB_NAME, DB_VERSION)?;
+
+        let upgrade_request = open_request.clone();
+        let on_upgrade_needed = Closure::once_into_js(move |_: JsValue| {
+            if let Ok(db) = upgrade_request.result() {
+                let db: IdbDatabase = db.unchecked_into();
+                if !db.object_store_names().contains(TASKS_STORE_NAME) {
+                    let _ = db.create_object_store(TASKS_STORE_NAME);
+                }
+            }
+        });
+        open_request.set_onupgradeneeded(Some(on_upgrade_needed.unchecked_ref()));
+
+        Ok(future_to_promise(async move { request_result(&open_request).await }))
+    };
+    open().unwrap_or_else(|error| Promise::reject(&error))
+}
+
+/// Waits for an IndexedDB request to finish and returns its result.
+async fn request_result(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    JsFuture::from(promise).await?;
+    request.result()
+}
@@ -0,0 +1,370 @@
+use std::{collections::HashMap, rc::Rc};
+
+use yewdux::{
+    listener::{init_listener, Listener},
+    prelude::*,
+};
+
+use crate::{
+    api::tasks::todo_task::TodoTask,
+    task_cache::{CachedTasks, TaskCache},
+    task_sync::init_task_sync,
+    SessionStore,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FetchState {
+    #[default]
+    Idle,
+    Loading,
+    Loaded,
+    Error,
+    /// A single task the server doesn't have.
+    NotFound,
+    /// A single task that belongs to another user.
+    Forbidden,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TaskEntry {
+    pub task: Option<TodoTask>,
+    pub state: FetchState,
+}
+
+/// Tasks of the logged in user, normalized by id.
+/// `list` holds the ids of the task list in the order the server returned them,
+/// it stays `None` until the list has been fetched once.
+#[derive(Default, PartialEq, Clone, Debug)]
+pub struct TaskStore {
+    entries: HashMap<i32, TaskEntry>,
+    list: Option<Vec<i32>>,
+    list_state: FetchState,
+    /// Bumped whenever the list is invalidated, so results of outdated fetches are dropped.
+    list_version: u32,
+    /// When the list comes from the cache, the time it was saved at.
+    stale_since: Option<String>,
+    /// ETag of the response the list was fetched from, lets refreshes skip an unchanged list.
+    list_etag: Option<String>,
+}
+
+impl Store for TaskStore {
+    fn new() -> Self {
+        init_listener(TaskCacheListener);
+        init_task_sync();
+        Default::default()
+    }
+
+    fn should_notify(&self, old: &Self) -> bool {
+        self != old
+    }
+}
+
+impl TaskStore {
+    pub fn list_state(&self) -> FetchState {
+        self.list_state
+    }
+
+    pub fn list_version(&self) -> u32 {
+        self.list_version
+    }
+
+    pub fn has_list(&self) -> bool {
+        self.list.is_some()
+    }
+
+    pub fn stale_since(&self) -> Option<&String> {
+        self.stale_since.as_ref()
+    }
+
+    pub fn list_etag(&self) -> Option<&String> {
+        self.list_etag.as_ref()
+    }
+
+    /// Tasks of the list in server order.
+    pub fn tasks(&self) -> Vec<TodoTask> {
+        match &self.list {
+            Some(list) => list.iter().filter_map(|id| self.get_task(*id).cloned()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Every tag used by a task of the list, sorted and without duplicates.
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for task in self.tasks() {
+            for tag in task.tags {
+                if !tags.iter().any(|existing| existing.eq_ignore_ascii_case(&tag)) {
+                    tags.push(tag);
+                }
+            }
+        }
+        tags.sort_by_key(|tag| tag.to_lowercase());
+        tags
+    }
+
+    pub fn get_task(&self, id: i32) -> Option<&TodoTask> {
+        self.entries.get(&id)?.task.as_ref()
+    }
+
+    pub fn task_state(&self, id: i32) -> FetchState {
+        match self.entries.get(&id) {
+            Some(entry) => entry.state,
+            None => FetchState::Idle,
+        }
+    }
+
+    /// Marks the list as loading and returns the version the fetch belongs to,
+    /// `None` if the list is already loaded or a fetch is in flight.
+    pub fn begin_list_fetch(&mut self) -> Option<u32> {
+        match self.list_state {
+            FetchState::Loading | FetchState::Loaded => None,
+            _ => {
+                self.list_state = FetchState::Loading;
+                Some(self.list_version)
+            }
+        }
+    }
+
+    /// Like `begin_list_fetch`, but also refreshes a list that is already loaded.
+    pub fn begin_list_refresh(&mut self) -> Option<u32> {
+        match self.list_state {
+            FetchState::Loading => None,
+            _ => {
+                self.list_state = FetchState::Loading;
+                Some(self.list_version)
+            }
+        }
+    }
+
+    pub fn finish_list_fetch(&mut self, version: u32, tasks: Vec<TodoTask>, etag: Option<String>) {
+        if version != self.list_version {
+            return;
+        }
+
+        let ids: Vec<i32> = tasks.iter().map(|task| task.id).collect();
+        // tasks missing from the new list were deleted elsewhere, unless they are being fetched right now,
+        // entries without a task only hold the outcome of a fetch
+        self.entries.retain(|id, entry| {
+            ids.contains(id) || entry.state == FetchState::Loading || entry.task.is_none()
+        });
+        for task in tasks {
+            self.set_loaded(task);
+        }
+        self.list = Some(ids);
+        self.list_state = FetchState::Loaded;
+        self.stale_since = None;
+        self.list_etag = etag;
+    }
+
+    /// The server answered that the list didn't change since `list_etag`.
+    pub fn finish_list_unchanged(&mut self, version: u32) {
+        if version != self.list_version {
+            return;
+        }
+
+        self.list_state = FetchState::Loaded;
+    }
+
+    /// Shows cached tasks while the list is being fetched, ignored once the fetch is done.
+    pub fn restore_cached(&mut self, version: u32, cached: CachedTasks) {
+        if version != self.list_version || self.list.is_some() {
+            return;
+        }
+
+        let ids = cached.tasks.iter().map(|task| task.id).collect();
+        for task in cached.tasks {
+            self.set_loaded(task);
+        }
+        self.list = Some(ids);
+        self.stale_since = Some(cached.saved_at);
+    }
+
+    /// An aborted fetch leaves the list idle, so the next mount fetches it again.
+    pub fn fail_list_fetch(&mut self, version: u32, aborted: bool) {
+        if version != self.list_version {
+            return;
+        }
+
+        self.list_state = match aborted {
+            true => FetchState::Idle,
+            false => FetchState::Error,
+        };
+    }
+
+    /// Makes the list fetch again, the current tasks are kept until it's done.
+    pub fn invalidate_list(&mut self) {
+        self.list_state = FetchState::Idle;
+        self.list_version = self.list_version.wrapping_add(1);
+        self.list_etag = None;
+    }
+
+    /// Marks a single task as loading, returns `false` if it's already being fetched.
+    pub fn begin_task_fetch(&mut self, id: i32) -> bool {
+        let entry = self.entries.entry(id).or_default();
+        if entry.state == FetchState::Loading {
+            return false;
+        }
+
+        entry.state = FetchState::Loading;
+        true
+    }
+
+    pub fn finish_task_fetch(&mut self, task: TodoTask) {
+        self.upsert_task(task);
+    }
+
+    pub fn fail_task_fetch(&mut self, id: i32, aborted: bool) {
+        if let Some(entry) = self.entries.get_mut(&id) {
+            entry.state = match (aborted, &entry.task) {
+                (true, Some(_)) => FetchState::Loaded,
+                (true, None) => FetchState::Idle,
+                (false, _) => FetchState::Error,
+            };
+        }
+    }
+
+    /// The server doesn't have the task or it isn't the user's, `state` is kept
+    /// so pages waiting on the same fetch can show why.
+    pub fn finish_task_missing(&mut self, id: i32, state: FetchState) {
+        self.remove_task(id);
+        self.entries.insert(id, TaskEntry { task: None, state });
+    }
+
+    /// Stores the task, adding it to the end of the list if the list is loaded and doesn't have it yet.
+    pub fn upsert_task(&mut self, task: TodoTask) {
+        if let Some(list) = self.list.as_mut() {
+            if !list.contains(&task.id) {
+                list.push(task.id);
+            }
+        }
+        self.set_loaded(task);
+    }
+
+    /// Removes the task, returning it along with its position in the list so it can be restored.
+    pub fn remove_task(&mut self, id: i32) -> Option<(usize, TodoTask)> {
+        let task = self.entries.remove(&id)?.task?;
+        let index = match self.list.as_mut() {
+            Some(list) => match list.iter().position(|task_id| *task_id == id) {
+                Some(index) => {
+                    list.remove(index);
+                    index
+                }
+                None => list.len(),
+            },
+            None => 0,
+        };
+        Some((index, task))
+    }
+
+    pub fn restore_task(&mut self, index: usize, task: TodoTask) {
+        if let Some(list) = self.list.as_mut() {
+            if !list.contains(&task.id) {
+                list.insert(index.min(list.len()), task.id);
+            }
+        }
+        self.set_loaded(task);
+    }
+
+    /// Tasks that were added or changed since `old`, and the ids of the ones that were removed.
+    pub fn changes_since(&self, old: &TaskStore) -> (Vec<TodoTask>, Vec<i32>) {
+        let changed = self
+            .entries
+            .values()
+            .filter_map(|entry| entry.task.as_ref())
+            .filter(|task| old.get_task(task.id) != Some(*task))
+            .cloned()
+            .collect();
+        let removed = old
+            .entries
+            .iter()
+            .filter(|(id, entry)| entry.task.is_some() && self.get_task(**id).is_none())
+            .map(|(id, _)| *id)
+            .collect();
+        (changed, removed)
+    }
+
+    fn set_loaded(&mut self, task: TodoTask) {
+        self.entries.insert(
+            task.id,
+            TaskEntry {
+                task: Some(task),
+                state: FetchState::Loaded,
+            },
+        );
+    }
+}
+
+/// Writes the task list to the cache of the logged in user whenever it changes.
+struct TaskCacheListener;
+
+impl Listener for TaskCacheListener {
+    type Store = TaskStore;
+
+    fn on_change(&mut self, store: Rc<TaskStore>) {
+        // tasks restored from the cache don't need to be written back
+        if !store.has_list() || store.stale_since.is_some() {
+            return;
+        }
+
+        if let Some(user) = Dispatch::<SessionStore>::new().get().user.clone() {
+            TaskCache::schedule_save(user.id, CachedTasks::new(store.tasks()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: i32) -> TodoTask {
+        TodoTask {
+            id,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn a_missing_task_keeps_the_outcome_of_its_fetch() {
+        let mut store = TaskStore::default();
+        store.upsert_task(task(1));
+        assert!(store.begin_task_fetch(1));
+        assert!(!store.begin_task_fetch(1));
+
+        store.finish_task_missing(1, FetchState::NotFound);
+
+        assert_eq!(store.get_task(1), None);
+        assert_eq!(store.task_state(1), FetchState::NotFound);
+    }
+
+    #[test]
+    fn a_list_fetch_keeps_the_outcome_of_task_fetches() {
+        let mut store = TaskStore::default();
+        store.finish_task_missing(1, FetchState::Forbidden);
+        let version = store.begin_list_fetch().unwrap();
+
+        store.finish_list_fetch(version, vec![task(2)], None);
+
+        assert_eq!(store.task_state(1), FetchState::Forbidden);
+        assert_eq!(store.tasks(), vec![task(2)]);
+    }
+
+    #[test]
+    fn a_missing_task_is_fetched_again() {
+        let mut store = TaskStore::default();
+        store.finish_task_missing(1, FetchState::NotFound);
+
+        assert!(store.begin_task_fetch(1));
+        assert_eq!(store.task_state(1), FetchState::Loading);
+    }
+
+    #[test]
+    fn a_failed_fetch_is_seen_by_every_subscriber() {
+        let mut store = TaskStore::default();
+        assert!(store.begin_task_fetch(1));
+        assert!(!store.begin_task_fetch(1));
+
+        store.fail_task_fetch(1, false);
+
+        assert_eq!(store.task_state(1), FetchState::Error);
+    }
+}
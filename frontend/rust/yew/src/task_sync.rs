@@ -0,0 +1,150 @@
+use std::{cell::Cell, rc::Rc};
+
+use gloo::{console::log, events::EventListener};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{BroadcastChannel, MessageEvent};
+use yewdux::{
+    listener::{init_listener, Listener},
+    prelude::*,
+};
+
+use crate::{api::tasks::todo_task::TodoTask, task_store::FetchState, SessionStore, TaskStore};
+
+const CHANNEL_NAME: &str = "todo-tasks";
+
+thread_local! {
+    static CHANNEL: Option<BroadcastChannel> = BroadcastChannel::new(CHANNEL_NAME).ok();
+    /// Tells messages sent by this tab apart from the ones of other tabs.
+    static TAB_ID: Uuid = Uuid::new_v4();
+    /// Set while a message from another tab is applied, so it isn't broadcast back.
+    static APPLYING_REMOTE: Cell<bool> = Cell::new(false);
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum TaskSyncEvent {
+    Upserted(TodoTask),
+    Removed(i32),
+    /// The list changed in a way the other tabs have to fetch again.
+    Invalidated,
+}
+
+impl TaskSyncEvent {
+    fn apply(self, store: &mut TaskStore) {
+        match self {
+            TaskSyncEvent::Upserted(task) => store.upsert_task(task),
+            TaskSyncEvent::Removed(task_id) => {
+                store.remove_task(task_id);
+            }
+            TaskSyncEvent::Invalidated => store.invalidate_list(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct TaskSyncMessage {
+    sender: Uuid,
+    user_id: u32,
+    event: TaskSyncEvent,
+}
+
+/// Starts broadcasting changes of the `TaskStore` to other tabs and applying theirs.
+/// Does nothing where `BroadcastChannel` isn't supported.
+pub fn init_task_sync() {
+    CHANNEL.with(|channel| {
+        if let Some(channel) = channel {
+            EventListener::new(channel, "message", on_message).forget();
+            init_listener(TaskSyncListener::default());
+        }
+    });
+}
+
+/// Tells this tab apart from the others for as long as it's open.
+pub fn tab_id() -> Uuid {
+    TAB_ID.with(|id| *id)
+}
+
+fn current_user_id() -> Option<u32> {
+    Dispatch::<SessionStore>::new().get().user.as_ref().map(|user| user.id)
+}
+
+fn broadcast(user_id: u32, event: TaskSyncEvent) {
+    let message = TaskSyncMessage {
+        sender: tab_id(),
+        user_id,
+        event,
+    };
+    let json = match serde_json::to_string(&message) {
+        Ok(json) => json,
+        Err(_) => return,
+    };
+
+    CHANNEL.with(|channel| {
+        if let Some(channel) = channel {
+            if let Err(error) = channel.post_message(&JsValue::from_str(&json)) {
+                log!("Unable to broadcast task change:", error);
+            }
+        }
+    });
+}
+
+fn on_message(event: &web_sys::Event) {
+    let json = match event.dyn_ref::<MessageEvent>().and_then(|event| event.data().as_string()) {
+        Some(json) => json,
+        None => return,
+    };
+    let message = match serde_json::from_str::<TaskSyncMessage>(&json) {
+        Ok(message) => message,
+        Err(_) => return,
+    };
+
+    // tabs of another user, e.g. after logging in as someone else, don't share tasks
+    if message.sender == tab_id() || current_user_id() != Some(message.user_id) {
+        return;
+    }
+
+    APPLYING_REMOTE.with(|applying| applying.set(true));
+    Dispatch::<TaskStore>::new().reduce_mut(|store| message.event.apply(store));
+    APPLYING_REMOTE.with(|applying| applying.set(false));
+}
+
+#[derive(Default)]
+struct TaskSyncListener {
+    last: Rc<TaskStore>,
+}
+
+impl Listener for TaskSyncListener {
+    type Store = TaskStore;
+
+    fn on_change(&mut self, store: Rc<TaskStore>) {
+        let last = std::mem::replace(&mut self.last, store.clone());
+        let user_id = match current_user_id() {
+            Some(user_id) => user_id,
+            None => return,
+        };
+
+        // a reset on logout or login, or a change that came from another tab
+        if *store == TaskStore::default() || APPLYING_REMOTE.with(|applying| applying.get()) {
+            return;
+        }
+
+        if store.list_version() != last.list_version() {
+            broadcast(user_id, TaskSyncEvent::Invalidated);
+        }
+
+        // fetched and cached lists are loaded by every tab on its own
+        let list_fetched = store.list_state() == FetchState::Loaded && last.list_state() != FetchState::Loaded;
+        if list_fetched || store.stale_since().is_some() {
+            return;
+        }
+
+        let (changed, removed) = store.changes_since(&last);
+        for task in changed {
+            broadcast(user_id, TaskSyncEvent::Upserted(task));
+        }
+        for task_id in removed {
+            broadcast(user_id, TaskSyncEvent::Removed(task_id));
+        }
+    }
+}
@@ -1,30 +1,54 @@
-use gloo::{console::log, timers::callback::Timeout};
+use gloo::{console::log, timers::callback::Timeout, utils::window};
 use lazy_static::__Deref;
 use uuid::Uuid;
 use yew::UseStateHandle;
 use yewdux::prelude::Dispatch;
 
 use crate::{
-    api::api_client::ApiError,
+    api::api_client::{ApiError, ApiErrorKind},
     components::{organisms::error_message::DEFAULT_TIMEOUT_MS, pages::error_data::ErrorData},
     SessionStore,
 };
 
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ErrorAction {
+    /// The session is no longer valid, the user has to log in again.
+    EndSession,
+    /// The failure is most likely temporary, the request can be repeated as is.
+    Retry,
+    /// The request was rejected, repeating it won't help.
+    Show,
+    /// The request was cancelled on purpose, there is nothing to report.
+    Ignore,
+}
+
+impl From<&ApiError> for ErrorAction {
+    fn from(error: &ApiError) -> Self {
+        match &error.kind {
+            // 403 is also what the backend answers with for a revoked token
+            ApiErrorKind::Http { status: 401 | 403, .. } => ErrorAction::EndSession,
+            ApiErrorKind::Http { status: 408 | 429 | 502..=504, .. } => ErrorAction::Retry,
+            ApiErrorKind::Http { .. } => ErrorAction::Show,
+            ApiErrorKind::Network(_) | ApiErrorKind::Timeout => ErrorAction::Retry,
+            ApiErrorKind::Deserialize(_) => ErrorAction::Show,
+            ApiErrorKind::Aborted => ErrorAction::Ignore,
+        }
+    }
+}
+
 pub fn handle_api_error(
     error: ApiError,
     session_dispatch: &Dispatch<SessionStore>,
     error_data: Option<UseStateHandle<ErrorData>>,
 ) {
-    log!(error.to_string());
-    match error {
-        ApiError::HttpStatus(code, _) => {
-            if code == 401u16 {
-                clear_user_store(session_dispatch);
-            }
-        },
-        ApiError::Parse(_) => clear_user_store(session_dispatch),
-        ApiError::Other(_) => clear_user_store(session_dispatch),
+    log!(format!("{} failed: {:?}", error.request, error.kind));
+    let action = ErrorAction::from(&error);
+    match action {
+        ErrorAction::EndSession => end_session(session_dispatch),
+        ErrorAction::Ignore => return,
+        _ => (),
     }
+    let message = error_message(&error, action);
 
     match error_data {
         Some(error_data) => {
@@ -41,19 +65,117 @@ pub fn handle_api_error(
             error_data.set(ErrorData::default());
 
             error_data.set(ErrorData {
-                message: error.to_string(),
+                message,
                 display: true,
                 uuid: error_uuid,
             });
         }
-        None => log!(error.to_string()),
+        None => log!(message),
     }
 }
 
-fn clear_user_store(dispatch: &Dispatch<SessionStore>) {
+/// The message shown for a failed request, a temporary failure asks the user to try again.
+fn error_message(error: &ApiError, action: ErrorAction) -> String {
+    match action {
+        ErrorAction::Retry => format!("{}. Please try again in a moment.", error.to_string().trim_end_matches(&['.', '!'][..])),
+        _ => error.to_string(),
+    }
+}
+
+/// Logs the user out because the session is no longer valid,
+/// remembering the current page so they can continue there after logging in again.
+pub fn end_session(dispatch: &Dispatch<SessionStore>) {
+    let return_to = window().location().pathname().ok();
     dispatch.reduce(|store| {
         let mut store = store.deref().clone();
+        if store.user.is_some() {
+            store.return_to = return_to;
+        }
         store.user = None;
         store.into()
     });
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::api_client::RequestInfo;
+
+    fn error(kind: ApiErrorKind) -> ApiError {
+        ApiError {
+            kind,
+            request: RequestInfo {
+                method: "GET".to_string(),
+                uri: "/tasks".to_string(),
+            },
+        }
+    }
+
+    fn http(status: u16) -> ApiError {
+        error(ApiErrorKind::Http {
+            status,
+            status_text: String::new(),
+            message: None,
+            code: None,
+            retry_after_ms: None,
+        })
+    }
+
+    #[test]
+    fn unauthorized_and_forbidden_end_the_session() {
+        for status in [401, 403] {
+            assert_eq!(ErrorAction::from(&http(status)), ErrorAction::EndSession, "status {}", status);
+        }
+    }
+
+    #[test]
+    fn temporary_failures_are_retried() {
+        for status in [408, 429, 502, 503, 504] {
+            assert_eq!(ErrorAction::from(&http(status)), ErrorAction::Retry, "status {}", status);
+        }
+        assert_eq!(ErrorAction::from(&error(ApiErrorKind::Network("offline".to_string()))), ErrorAction::Retry);
+        assert_eq!(ErrorAction::from(&error(ApiErrorKind::Timeout)), ErrorAction::Retry);
+    }
+
+    #[test]
+    fn cancelled_requests_are_ignored() {
+        assert_eq!(ErrorAction::from(&error(ApiErrorKind::Aborted)), ErrorAction::Ignore);
+    }
+
+    #[test]
+    fn temporary_failures_ask_to_try_again() {
+        let timeout = error(ApiErrorKind::Timeout);
+        assert_eq!(
+            error_message(&timeout, ErrorAction::from(&timeout)),
+            "The server took too long to respond. Please try again in a moment."
+        );
+        let throttled = error(ApiErrorKind::Http {
+            status: 429,
+            status_text: "Too Many Requests".to_string(),
+            message: Some("Slow down!".to_string()),
+            code: None,
+            retry_after_ms: None,
+        });
+        assert_eq!(error_message(&throttled, ErrorAction::from(&throttled)), "Slow down. Please try again in a moment.");
+    }
+
+    #[test]
+    fn rejected_requests_show_the_error_as_is() {
+        let rejected = error(ApiErrorKind::Http {
+            status: 400,
+            status_text: "Bad Request".to_string(),
+            message: Some("Task not found!".to_string()),
+            code: None,
+            retry_after_ms: None,
+        });
+        assert_eq!(error_message(&rejected, ErrorAction::from(&rejected)), "Task not found!");
+    }
+
+    #[test]
+    fn rejected_requests_are_shown() {
+        for status in [400, 404, 409, 422, 500, 501] {
+            assert_eq!(ErrorAction::from(&http(status)), ErrorAction::Show, "status {}", status);
+        }
+        assert_eq!(ErrorAction::from(&error(ApiErrorKind::Deserialize("bad json".to_string()))), ErrorAction::Show);
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::Deserialize;
+
+use crate::{api::api_client::ApiError, components::atoms::date_time_picker::parse_local};
+
+/// The backend rejects shorter task titles.
+pub const TITLE_MIN_LENGTH: usize = 3;
+/// `tasks.title` is a VARCHAR(255).
+pub const TITLE_MAX_LENGTH: usize = 255;
+/// `users.username` is a VARCHAR(64).
+pub const USERNAME_MAX_LENGTH: usize = 64;
+/// The backend uses the default password rules of ASP.NET Identity.
+pub const PASSWORD_MIN_LENGTH: usize = 6;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Rule {
+    Required,
+    MinLength(usize),
+    MaxLength(usize),
+    /// The value has to pass `check`, `message` tells the user what is expected.
+    Pattern { check: fn(&str) -> bool, message: &'static str },
+}
+
+impl Rule {
+    /// The error for `value`, only `Required` complains about an empty value.
+    pub fn check(&self, value: &str) -> Option<String> {
+        let length = value.chars().count();
+        match self {
+            Rule::Required if value.trim().is_empty() => Some("This field is required".to_string()),
+            _ if value.is_empty() => None,
+            Rule::MinLength(min) if length < *min => Some(format!("Must be at least {} characters long", min)),
+            Rule::MaxLength(max) if length > *max => Some(format!("Can't be longer than {} characters", max)),
+            Rule::Pattern { check, message } if !check(value) => Some(message.to_string()),
+            _ => None,
+        }
+    }
+}
+
+pub const USERNAME_RULES: &[Rule] = &[
+    Rule::Required,
+    Rule::MaxLength(USERNAME_MAX_LENGTH),
+    Rule::Pattern {
+        check: is_username,
+        message: "Only letters, digits and - . _ @ + are allowed",
+    },
+];
+
+/// Existing passwords are only checked for being there, the rules apply to new ones.
+pub const LOGIN_PASSWORD_RULES: &[Rule] = &[Rule::Required];
+
+pub const NEW_PASSWORD_RULES: &[Rule] = &[
+    Rule::Required,
+    Rule::MinLength(PASSWORD_MIN_LENGTH),
+    Rule::Pattern {
+        check: |value| value.chars().any(|c| c.is_ascii_digit()),
+        message: "Must contain a digit",
+    },
+    Rule::Pattern {
+        check: |value| value.chars().any(|c| c.is_lowercase()),
+        message: "Must contain a lowercase letter",
+    },
+    Rule::Pattern {
+        check: |value| value.chars().any(|c| c.is_uppercase()),
+        message: "Must contain an uppercase letter",
+    },
+    Rule::Pattern {
+        check: |value| value.chars().any(|c| !c.is_alphanumeric()),
+        message: "Must contain a symbol",
+    },
+];
+
+pub const TITLE_RULES: &[Rule] = &[
+    Rule::Required,
+    Rule::MinLength(TITLE_MIN_LENGTH),
+    Rule::MaxLength(TITLE_MAX_LENGTH),
+];
+
+/// A due date is optional, one that was entered has to exist in the user's timezone.
+pub const DUE_AT_RULES: &[Rule] = &[Rule::Pattern {
+    check: |value| parse_local(value).is_some(),
+    message: "Must be a valid date and time",
+}];
+
+fn is_username(value: &str) -> bool {
+    value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-._@+".contains(c))
+}
+
+/// The first rule `value` breaks.
+pub fn validate(value: &str, rules: &[Rule]) -> Option<String> {
+    rules.iter().find_map(|rule| rule.check(value))
+}
+
+/// Errors of a form keyed by field id, the form can only be submitted while there are none.
+/// Errors are only shown once the user has touched the field.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FormErrors {
+    errors: HashMap<String, String>,
+    touched: HashSet<String>,
+}
+
+impl FormErrors {
+    /// Checks every field at once when the form is first shown, without showing the errors yet.
+    pub fn new(fields: &[(&str, &str, &[Rule])]) -> Self {
+        let mut errors = Self::default();
+        for (field, value, rules) in fields {
+            if let Some(error) = validate(value, rules) {
+                errors.errors.insert(field.to_string(), error);
+            }
+        }
+        errors
+    }
+
+    /// The error to show next to the field.
+    pub fn get(&self, field: &str) -> Option<String> {
+        self.errors.get(field).filter(|_| self.touched.contains(field)).cloned()
+    }
+
+    pub fn set(&mut self, field: &str, error: Option<String>) {
+        self.touched.insert(field.to_string());
+        match error {
+            Some(error) => self.errors.insert(field.to_string(), error),
+            None => self.errors.remove(field),
+        };
+    }
+
+    pub fn check(&mut self, field: &str, value: &str, rules: &[Rule]) {
+        self.set(field, validate(value, rules));
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Puts the parts of a rejected request's message that mention one of the fields next to that field.
+    /// `fields` pairs field ids with the word the backend uses for them.
+    /// Returns `false` if nothing could be attributed, so the error is shown as a whole instead.
+    pub fn apply_server_error(&mut self, error: &ApiError, fields: &[(&str, &str)]) -> bool {
+        let mut applied = false;
+        for (name, message) in server_messages(error) {
+            let field = fields.iter().find(|(_, keyword)| {
+                let keyword = keyword.to_lowercase();
+                name.as_deref().map_or(false, |name| name.to_lowercase() == keyword)
+                    || message.to_lowercase().contains(&keyword)
+            });
+            if let Some((field, _)) = field {
+                self.set(field, Some(message));
+                applied = true;
+            }
+        }
+        applied
+    }
+}
+
+/// Validation failures ASP.NET reports on its own, before our controllers run.
+#[derive(Deserialize)]
+struct ProblemDetails {
+    errors: BTreeMap<String, Vec<String>>,
+}
+
+/// Messages of an error response with the name of the field they're about, where the backend says.
+/// Our controllers send plain messages joined by newlines.
+fn server_messages(error: &ApiError) -> Vec<(Option<String>, String)> {
+    let message = match error.message() {
+        Some(message) => message,
+        None => return Vec::new(),
+    };
+
+    match serde_json::from_str::<ProblemDetails>(message) {
+        Ok(problem) => problem
+            .errors
+            .into_iter()
+            .flat_map(|(field, messages)| messages.into_iter().map(move |message| (Some(field.clone()), message)))
+            .collect(),
+        Err(_) => message
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| (None, line.to_string()))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::api_client::{ApiErrorKind, RequestInfo};
+
+    fn bad_request(message: &str) -> ApiError {
+        ApiError::new(
+            ApiErrorKind::Http {
+                status: 400,
+                status_text: "Bad Request".to_string(),
+                message: Some(message.to_string()),
+                code: None,
+                retry_after_ms: None,
+            },
+            RequestInfo {
+                method: "POST".to_string(),
+                uri: "/tasks".to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn empty_or_whitespace_title_is_required() {
+        assert_eq!(validate("", TITLE_RULES), Some("This field is required".to_string()));
+        assert_eq!(validate("   ", TITLE_RULES), Some("This field is required".to_string()));
+    }
+
+    #[test]
+    fn title_has_a_minimum_length() {
+        assert_eq!(validate("ab", TITLE_RULES), Some("Must be at least 3 characters long".to_string()));
+        assert_eq!(validate("abc", TITLE_RULES), None);
+    }
+
+    #[test]
+    fn title_fits_its_column() {
+        assert_eq!(validate(&"a".repeat(TITLE_MAX_LENGTH), TITLE_RULES), None);
+        assert_eq!(
+            validate(&"a".repeat(TITLE_MAX_LENGTH + 1), TITLE_RULES),
+            Some("Can't be longer than 255 characters".to_string())
+        );
+        // the column counts characters, not bytes
+        assert_eq!(validate(&"ż".repeat(TITLE_MAX_LENGTH), TITLE_RULES), None);
+    }
+
+    #[test]
+    fn username_fits_its_column_and_pattern() {
+        assert_eq!(validate(&"a".repeat(USERNAME_MAX_LENGTH), USERNAME_RULES), None);
+        assert_eq!(
+            validate(&"a".repeat(USERNAME_MAX_LENGTH + 1), USERNAME_RULES),
+            Some("Can't be longer than 64 characters".to_string())
+        );
+        assert_eq!(validate("jan.kowalski@mail", USERNAME_RULES), None);
+        assert_eq!(
+            validate("jan kowalski", USERNAME_RULES),
+            Some("Only letters, digits and - . _ @ + are allowed".to_string())
+        );
+    }
+
+    #[test]
+    fn new_password_reports_the_first_broken_rule() {
+        assert_eq!(validate("Ab1!", NEW_PASSWORD_RULES), Some("Must be at least 6 characters long".to_string()));
+        assert_eq!(validate("Abcdef!", NEW_PASSWORD_RULES), Some("Must contain a digit".to_string()));
+        assert_eq!(validate("ABCDE1!", NEW_PASSWORD_RULES), Some("Must contain a lowercase letter".to_string()));
+        assert_eq!(validate("abcde1!", NEW_PASSWORD_RULES), Some("Must contain an uppercase letter".to_string()));
+        assert_eq!(validate("Abcde12", NEW_PASSWORD_RULES), Some("Must contain a symbol".to_string()));
+        assert_eq!(validate("Abcde1!", NEW_PASSWORD_RULES), None);
+        // existing passwords only have to be there
+        assert_eq!(validate("a", LOGIN_PASSWORD_RULES), None);
+    }
+
+    #[test]
+    fn due_date_is_optional_but_has_to_be_valid() {
+        assert_eq!(validate("", DUE_AT_RULES), None);
+        assert_eq!(validate("2023-03-14T09:00", DUE_AT_RULES), None);
+        assert_eq!(validate("2023-02-30T09:00", DUE_AT_RULES), Some("Must be a valid date and time".to_string()));
+        assert_eq!(validate("14/03/2023 09:00", DUE_AT_RULES), Some("Must be a valid date and time".to_string()));
+    }
+
+    #[test]
+    fn errors_are_shown_once_the_field_is_touched() {
+        let mut errors = FormErrors::new(&[("title", "", TITLE_RULES)]);
+        assert!(!errors.is_valid());
+        assert_eq!(errors.get("title"), None);
+
+        errors.check("title", "ab", TITLE_RULES);
+        assert_eq!(errors.get("title"), Some("Must be at least 3 characters long".to_string()));
+
+        errors.check("title", "Release v2", TITLE_RULES);
+        assert_eq!(errors.get("title"), None);
+        assert!(errors.is_valid());
+    }
+
+    #[test]
+    fn server_messages_are_put_next_to_the_fields_they_mention() {
+        let mut errors = FormErrors::default();
+        let error = bad_request("Title is too short!\nSomething else went wrong");
+        assert!(errors.apply_server_error(&error, &[("title", "title")]));
+        assert_eq!(errors.get("title"), Some("Title is too short!".to_string()));
+    }
+
+    #[test]
+    fn problem_details_are_put_next_to_the_named_fields() {
+        let mut errors = FormErrors::default();
+        let error = bad_request(r#"{"errors": {"Title": ["The Title field is required."]}}"#);
+        assert!(errors.apply_server_error(&error, &[("title", "title")]));
+        assert_eq!(errors.get("title"), Some("The Title field is required.".to_string()));
+    }
+
+    #[test]
+    fn unattributed_server_errors_are_left_to_the_caller() {
+        let mut errors = FormErrors::default();
+        assert!(!errors.apply_server_error(&bad_request("Invalid user!"), &[("title", "title")]));
+        assert!(errors.is_valid());
+    }
+}
@@ -11,6 +11,18 @@ describe("todo app", () => {
     });
   });
 
+  describe("unknown pages", () => {
+    it("should show a not found page for unknown URLs", () => {
+      cy.visit("/tasks/abc")
+        .dget("not-found")
+        .should("contain", "Page not found")
+        .dget("goto-home")
+        .click()
+        .location("pathname")
+        .should("eq", "/");
+    });
+  });
+
   describe("creating an account", () => {
     it("should be able to create an account", () => {
       const username = faker.internet.userName();
@@ -26,6 +38,34 @@ describe("todo app", () => {
         .url()
         .should("not.contain", "/create-account");
     });
+
+    it("should not submit an invalid form", () => {
+      cy.visit("/create-account")
+        .dget("submit")
+        .should("be.disabled")
+        .dget("username")
+        .type("not a valid name")
+        .dget("username-error")
+        .should("contain", "Only letters, digits")
+        .dget("password")
+        .type("short")
+        .dget("password-error")
+        .should("contain", "at least 6 characters")
+        .dget("submit")
+        .should("be.disabled")
+        .dget("username")
+        .clear()
+        .type(faker.internet.userName())
+        .dget("username-error")
+        .should("not.exist")
+        .dget("password")
+        .clear()
+        .type(faker.internet.password(20, true, /[a-zA-Z]/, '34$Ds'))
+        .dget("password-error")
+        .should("not.exist")
+        .dget("submit")
+        .should("not.be.disabled");
+    });
   });
 
   describe("logging into an account", () => {
@@ -145,6 +185,71 @@ describe("todo app", () => {
         .should("contain", "T");
     });
 
+    it("should track subtasks and complete them all at once", () => {
+      cy.dget("tasklink")
+        .last()
+        .click()
+        .dget("edit")
+        .click()
+        .dget("editing-subtasks-new")
+        .type("Write notes{enter}")
+        .type("Tag release{enter}")
+        .type("Publish{enter}")
+        .dget("editing-subtasks-item")
+        .should("have.length", 3)
+        .dget("submit")
+        .click()
+        .dget("subtasks-item")
+        .should("have.length", 3)
+        .first()
+        .find("input")
+        .click({ force: true })
+        .dget("subtasks")
+        .should("contain", "Subtasks (1/3)")
+        .dget("complete-subtasks")
+        .click()
+        .dget("subtasks")
+        .should("contain", "Subtasks (3/3)")
+        .dget("completed")
+        .should("not.contain", "Task not yet completed")
+        .dget("logo")
+        .click()
+        .dget("subtask-progress")
+        .should("contain", "3/3");
+    });
+
+    it("should create the next occurrence when a recurring task is completed", () => {
+      cy.dget("tasklink")
+        .last()
+        .click()
+        .dget("edit")
+        .click()
+        .dget("editing-recurrence-frequency")
+        .select("weekly")
+        .dget("editing-recurrence-weekday")
+        .should("have.length", 7)
+        .dget("editing-recurrence-frequency")
+        .select("daily")
+        .dget("submit")
+        .click()
+        .dget("recurrence")
+        .should("contain", "Every day")
+        .dget("next-due-at")
+        .should("exist")
+        .dget("logo")
+        .click()
+        .dget("completed")
+        .last()
+        .click()
+        .dget("tasklink")
+        .should("have.length", 3)
+        .dget("recurrence")
+        .should("have.length", 1)
+        .dget("completed")
+        .last()
+        .should("not.be.checked");
+    });
+
     it("should be deletable", () => {
       cy.dget("tasklink")
         .should("have.length", 2)
@@ -205,6 +310,45 @@ describe("todo app", () => {
         .should("contain", description);
     });
 
+    it("should highlight an overdue task", () => {
+      const title = faker.lorem.sentence();
+
+      cy.createTask({ title, dueAt: "2020-01-01T10:00" })
+        .wait(1000)
+        .dget("tasklink")
+        .last()
+        .should("contain", title)
+        .parents("[data-due]")
+        .should("have.attr", "data-due", "overdue")
+        .find("[data-test=due-at]")
+        .should("contain", "01/01/20")
+        .dget("filter-status")
+        .select("Incomplete")
+        .dget("filter-date-field")
+        .select("Due")
+        .dget("tasklink")
+        .should("have.length", 1)
+        .and("contain", title);
+    });
+
+    it("should tag a task and filter by its tags", () => {
+      const title = faker.lorem.sentence();
+
+      cy.createTask({ title, tags: ["work", "urgent"] })
+        .wait(1000)
+        .dget("tasklink")
+        .last()
+        .should("contain", title)
+        .dget("tags-chip")
+        .should("contain", "work")
+        .and("contain", "urgent")
+        .get("[data-test=tag-filter-chip][data-tag=work]")
+        .click()
+        .dget("tasklink")
+        .should("have.length", 1)
+        .and("contain", title);
+    });
+
     it("should be able to cancel while creating a task", () => {
       cy.dget("add-task")
         .click()
@@ -241,6 +385,59 @@ describe("todo app", () => {
     });
   });
 
+  describe("live updates", () => {
+    const cors = { "access-control-allow-origin": "*" };
+    const pushedTask = {
+      id: 999999,
+      title: "Created on another device",
+      priority: "C",
+      description: null,
+      created_at: null,
+      completed_at: null,
+      user_id: 1,
+    };
+
+    it("should apply the task events the server sends", () => {
+      const username = faker.internet.userName();
+      const password = faker.internet.password(20, true, /[a-zA-Z]/, '34$Ds');
+
+      cy.intercept({ method: "GET", url: /\/tasks$/ }).as("getTasks");
+      cy.createAccount(username, password)
+        .wait("@getTasks")
+        .then(({ request }) => {
+          const apiUri = request.url.replace(/\/tasks$/, "");
+          // a mock event server, the stream is only opened with the ticket and never with the token
+          cy.intercept(
+            { method: "POST", url: `${apiUri}/tasks/events/ticket` },
+            { headers: cors, body: { data: { ticket: "mock-ticket" } } }
+          );
+          cy.intercept(
+            {
+              method: "GET",
+              pathname: `${new URL(apiUri).pathname}/tasks/events`,
+              query: { ticket: "mock-ticket" },
+            },
+            {
+              headers: { ...cors, "content-type": "text/event-stream" },
+              body: `event: created\ndata: ${JSON.stringify(pushedTask)}\n\n`,
+              delay: 500,
+            }
+          ).as("events");
+          cy.visit("/", {
+            onBeforeLoad(win) {
+              win.__TODO_API_CONFIG__ = { api_uri: apiUri, live_updates: true };
+            },
+          });
+        })
+        .wait("@events")
+        .its("request.url")
+        .should("not.contain", "token=")
+        .dget("tasklink")
+        .should("have.length", 3)
+        .and("contain", pushedTask.title);
+    });
+  });
+
   describe("logged out", () => {
     it("I should not be able to see any tasks", () => {
       cy.visit("/").dget("tasklink").should("have.length", 0);
@@ -282,8 +479,8 @@ describe("todo app", () => {
       cy.dget("completed")
         .first()
         .click({ force: true })
-        .dget("filter")
-        .select("Completed tasks")
+        .dget("filter-status")
+        .select("Completed")
         .dget("tasklink")
         .should("have.length", 1)
         .dget("tasklink")
@@ -291,14 +488,16 @@ describe("todo app", () => {
           "contain",
           "I am a task, you can complete me by checking the box"
         )
-        .dget("filter")
-        .select("Incompleted tasks")
+        .dget("filter-status")
+        .select("Incomplete")
         .dget("tasklink")
         .should("have.length", 1)
         .dget("tasklink")
         .should("contain", "See my details by clicking me")
-        .dget("filter")
-        .select("Priority A")
+        .dget("filter-status")
+        .select("Any")
+        .get("[data-test=filter-priority][data-tag=A]")
+        .click()
         .dget("tasklink")
         .should("have.length", 1)
         .dget("tasklink")
@@ -306,32 +505,97 @@ describe("todo app", () => {
           "contain",
           "I am a task, you can complete me by checking the box"
         )
-        .dget("filter")
-        .select("Priority B")
-        .dget("tasklink")
-        .should("have.length", 1)
+        .get("[data-test=filter-priority][data-tag=B]")
+        .click()
         .dget("tasklink")
-        .should("contain", "See my details by clicking me")
-        .dget("filter")
-        .select("Priority C")
+        .should("have.length", 2)
+        .get("[data-test=filter-priority][data-tag=A]")
+        .click()
+        .get("[data-test=filter-priority][data-tag=B]")
+        .click()
+        .get("[data-test=filter-priority][data-tag=C]")
+        .click()
         .dget("tasklink")
         .should("have.length", 0)
-        .dget("filter")
-        .select("None")
+        .dget("clear-filters")
+        .click()
         .dget("tasklink")
         .should("have.length", 2);
     });
+
+    it("should combine filters and remove them through their chips", () => {
+      cy.dget("filter-status")
+        .select("Incomplete")
+        .get("[data-test=filter-priority][data-tag=A]")
+        .click()
+        .dget("active-filter")
+        .should("have.length", 2)
+        .dget("tasklink")
+        .should("have.length", 1)
+        .and("contain", "I am a task, you can complete me by checking the box")
+        .dget("filter-combinator")
+        .select("Any condition")
+        .dget("tasklink")
+        .should("have.length", 2)
+        .dget("filter-combinator")
+        .select("All conditions")
+        .dget("active-filter")
+        .contains("Priority A")
+        .find("button")
+        .click()
+        .dget("active-filter")
+        .should("have.length", 1)
+        .dget("tasklink")
+        .should("have.length", 2)
+        .dget("filter-text")
+        .type("details")
+        .dget("tasklink")
+        .should("have.length", 1)
+        .and("contain", "See my details by clicking me")
+        .dget("filter-text")
+        .clear()
+        .type("no task says this")
+        .dget("tasklink")
+        .should("have.length", 0);
+    });
   });
 
   describe("error messages", () => {
-    it("should display when I navigate to a single task while logged out", () => {
+    it("should ask me to log in when I navigate to a single task while logged out", () => {
       cy.visit("/tasks/1")
-        .dget("error")
+        .location("pathname")
+        .should("eq", "/login")
+        .location("search")
+        .should("contain", "next=%2Ftasks%2F1")
+        .dget("login-required")
         .should("be.visible")
-        .and("contain", "You must be logged in to view tasks")
-        .wait(31000)
-        .dget("error")
-        .should("not.be.visible");
+        .and("contain", "You must be logged in to view this page");
+    });
+
+    it("should return me to the task after logging in", () => {
+      const username = faker.internet.userName();
+      const password = faker.internet.password(20, true, /[a-zA-Z]/, '34$Ds');
+
+      cy.createAccount(username, password)
+        .dget("tasklink")
+        .first()
+        .click()
+        .location("pathname")
+        .then((taskPath) => {
+          cy.window()
+            .then((win) => win.sessionStorage.clear())
+            .visit(taskPath)
+            .location("pathname")
+            .should("eq", "/login")
+            .dget("username")
+            .type(username)
+            .dget("password")
+            .type(password)
+            .dget("submit")
+            .click()
+            .location("pathname")
+            .should("eq", taskPath);
+        });
     });
   });
 });
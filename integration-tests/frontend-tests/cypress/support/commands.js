@@ -41,7 +41,13 @@ Cypress.Commands.add("dget", (selector) => {
 });
 
 Cypress.Commands.add("login", (username, password) => {
-  cy.visit("/login")
+  // logged in users are sent away from the login page, so the session is dropped first
+  cy.window()
+    .then((win) => {
+      win.sessionStorage.clear();
+      win.localStorage.clear();
+    })
+    .visit("/login")
     .dget("username")
     .type(username)
     .dget("password")
@@ -56,6 +62,8 @@ Cypress.Commands.add(
     title = faker.lorem.sentence(),
     description = faker.lorem.sentences(3),
     priority = "B",
+    dueAt = null,
+    tags = [],
   }) => {
     cy.dget("add-task")
       .click()
@@ -64,8 +72,11 @@ Cypress.Commands.add(
       .dget("description")
       .type(description)
       .dget("priority")
-      .select(priority)
-      .dget("submit")
-      .click();
+      .select(priority);
+    if (dueAt) {
+      cy.dget("due-at").type(dueAt);
+    }
+    tags.forEach((tag) => cy.dget("tags").type(`${tag}{enter}`));
+    cy.dget("submit").click();
   }
 );